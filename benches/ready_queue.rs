@@ -0,0 +1,114 @@
+// Times the simulation on large workloads, where the ready queue dominates, against the
+// BTreeMap queue and linear scans it replaced. Run with `cargo bench --bench ready_queue`; pass
+// task counts to override the defaults of 1M for the heap and 20k for the baseline, which is
+// quadratic in the number of tasks.
+use fractal_interview::sim::{predict_finish_times, Projection};
+use fractal_interview::Task;
+use std::collections::BTreeMap;
+use std::time::Instant;
+
+type Simulate = fn(Vec<Task>) -> Vec<Projection>;
+
+/// `count` tasks with pseudo-random durations in 1..=100, arriving `spacing` apart.
+fn workload(count: u64, spacing: u32) -> Vec<Task> {
+    let mut state = 0x2545_f491_4f6c_dd1d_u64;
+    (0..count)
+        .map(|id| {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            Task {
+                id,
+                queued_at: id as u32 * spacing,
+                execution_duration: (state % 100) as u32 + 1,
+                ..Default::default()
+            }
+        })
+        .collect()
+}
+
+/// The simulation loop as it was before the heap, cut down to what these workloads use: the
+/// queue is a BTreeMap keyed by (duration, id, sequence number), every step scans the pending
+/// tasks for arrivals and the queue for anyone who has given up.
+fn baseline(tasks: Vec<Task>) -> Vec<Projection> {
+    let mut tasks: Vec<(u64, Task)> = (0..).zip(tasks).collect();
+    tasks.sort_by_key(|(seq, task)| (task.queued_at, *seq));
+
+    let mut projections = vec![];
+    let mut time = 0_u32;
+    let mut q: BTreeMap<(u32, u64, u64), Task> = BTreeMap::new();
+    while !tasks.is_empty() || !q.is_empty() {
+        if let Some(index) = tasks.iter().rposition(|(_, task)| task.queued_at <= time) {
+            for (seq, task) in tasks.drain(..index + 1) {
+                q.insert((task.execution_duration, task.id, seq), task);
+            }
+        }
+
+        let gave_up: Vec<_> = q
+            .iter()
+            .filter(|(_, task)| {
+                task.give_up_after
+                    .is_some_and(|after| task.queued_at.saturating_add(after) < time)
+            })
+            .map(|(key, _)| *key)
+            .collect();
+        for key in gave_up {
+            q.remove(&key);
+        }
+
+        let key = q.keys().next().cloned();
+        match key.and_then(|key| q.remove(&key)) {
+            Some(task) => {
+                projections.push(Projection {
+                    id: task.id,
+                    started_at: time,
+                    finished_at: time + task.execution_duration,
+                });
+                time += task.execution_duration;
+            }
+            None => match tasks.first() {
+                Some((_, task)) => time = task.queued_at,
+                None => break,
+            },
+        }
+    }
+    projections
+}
+
+fn main() {
+    let mut counts = std::env::args().skip(1).filter_map(|arg| arg.parse().ok());
+    let count = counts.next().unwrap_or(1_000_000);
+    let baseline_count = counts.next().unwrap_or(20_000);
+
+    // everything queued at once, so the queue holds every task; and arrivals at about the
+    // rate they can be served, so it stays short
+    let workloads = [("backlogged", 0), ("steady", 50)];
+    let runs: [(&str, u64, Simulate); 3] = [
+        ("heap", count, predict_finish_times),
+        ("heap", baseline_count, predict_finish_times),
+        ("baseline", baseline_count, baseline),
+    ];
+    for (group, count, simulate) in runs.iter().copied() {
+        for (name, spacing) in workloads.iter().copied() {
+            let tasks = workload(count, spacing);
+            let start = Instant::now();
+            let projections = simulate(tasks);
+            let elapsed = start.elapsed();
+            if group == "baseline" {
+                assert_eq!(
+                    projections,
+                    predict_finish_times(workload(count, spacing)),
+                    "the baseline disagrees with the simulation on {}",
+                    name
+                );
+            }
+            println!(
+                "{:<10}{:<12}{} tasks in {:?}",
+                group,
+                name,
+                projections.len(),
+                elapsed
+            );
+        }
+    }
+}
@@ -0,0 +1,220 @@
+// Content hashes of workloads and results, and an on-disk cache of schedules keyed by them, so
+// sweeps that repeat a simulation don't redo it.
+use crate::rng::fnv1a;
+use crate::{
+    AgingScheduler, BatchScheduler, CostOfDelayScheduler, CreditScheduler, DecayScheduler,
+    EdfScheduler, FairScheduler, LotteryScheduler, MlfqScheduler, MultiCoreScheduler,
+    PriorityScheduler, RoundRobinScheduler, Schedule, Scheduler, Semantics, SjfScheduler,
+    SrtfScheduler, Task,
+};
+use serde::Serialize;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// A hash of `value`'s JSON form. It is the same on every run and platform, so it can name
+/// results on disk or be compared across machines. It isn't cryptographic.
+pub fn content_hash<T: Serialize + ?Sized>(value: &T) -> u64 {
+    let json = serde_json::to_vec(value).expect("value serializes to JSON");
+    fnv1a(&json)
+}
+
+/// A policy whose runs can be cached.
+pub trait CacheKey {
+    /// Spells out every parameter that affects the policy's schedules, the same on every build
+    /// and run. `None` if the policy can't be identified that way, say because it holds a
+    /// function: a function's address changes between builds, and two functions can't be told
+    /// apart by their behaviour.
+    fn cache_key(&self) -> Option<String>;
+}
+
+/// Policies identified by their `Debug` form, which spells out every parameter.
+macro_rules! debug_key {
+    ($($policy:ty),*) => {
+        $(impl CacheKey for $policy {
+            fn cache_key(&self) -> Option<String> {
+                Some(format!("{:?}", self))
+            }
+        })*
+    };
+}
+
+debug_key!(
+    BatchScheduler,
+    CreditScheduler,
+    FairScheduler,
+    LotteryScheduler,
+    MlfqScheduler,
+    RoundRobinScheduler
+);
+
+/// Policies identified by their `Debug` form unless they break ties with a custom function.
+macro_rules! semantics_key {
+    ($($policy:ty),*) => {
+        $(impl CacheKey for $policy {
+            fn cache_key(&self) -> Option<String> {
+                (!matches!(self.semantics, Semantics::Custom(_))).then(|| format!("{:?}", self))
+            }
+        })*
+    };
+}
+
+semantics_key!(
+    AgingScheduler,
+    CostOfDelayScheduler,
+    EdfScheduler,
+    MultiCoreScheduler,
+    PriorityScheduler,
+    SjfScheduler,
+    SrtfScheduler
+);
+
+impl CacheKey for DecayScheduler {
+    fn cache_key(&self) -> Option<String> {
+        None
+    }
+}
+
+/// Identifies a run of `tasks` under `policy`, or `None` if the policy has no `cache_key`.
+pub fn run_key<P: CacheKey + ?Sized>(policy: &P, tasks: &[Task]) -> Option<u64> {
+    let policy = policy.cache_key()?;
+    Some(content_hash(&(policy, tasks)))
+}
+
+/// Schedules stored as JSON files in a directory, one per `run_key`.
+#[derive(Debug, Clone)]
+pub struct ScheduleCache {
+    dir: PathBuf,
+}
+
+impl ScheduleCache {
+    /// A cache in `dir`, which is created if needed.
+    pub fn new<P: AsRef<Path>>(dir: P) -> io::Result<Self> {
+        fs::create_dir_all(&dir)?;
+        Ok(ScheduleCache {
+            dir: dir.as_ref().to_path_buf(),
+        })
+    }
+
+    /// The schedule of `tasks` under `policy`, from the cache if this run has been done before.
+    /// Otherwise it is simulated and stored. An unreadable cache entry is treated as missing and
+    /// overwritten. A policy without a `cache_key` is always simulated, and nothing is stored.
+    pub fn schedule<P: Scheduler + CacheKey>(
+        &self,
+        policy: &P,
+        tasks: Vec<Task>,
+    ) -> io::Result<Schedule> {
+        let key = match run_key(policy, &tasks) {
+            Some(key) => key,
+            None => return Ok(policy.schedule(tasks)),
+        };
+        let path = self.dir.join(format!("{:016x}.json", key));
+        if let Ok(json) = fs::read(&path) {
+            if let Ok(schedule) = serde_json::from_slice(&json) {
+                return Ok(schedule);
+            }
+        }
+
+        let schedule = policy.schedule(tasks);
+        fs::write(&path, schedule.to_json())?;
+        Ok(schedule)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::examples::srtf_textbook;
+    use crate::scheduler::TieComparator;
+    use std::cell::Cell;
+
+    /// SJF, counting how often it actually runs.
+    #[derive(Default)]
+    struct Counting {
+        calls: Cell<usize>,
+    }
+
+    // the call count is left out, or it would change the key
+    impl CacheKey for Counting {
+        fn cache_key(&self) -> Option<String> {
+            Some("Counting".to_string())
+        }
+    }
+
+    impl Scheduler for Counting {
+        fn schedule(&self, tasks: Vec<Task>) -> Schedule {
+            self.calls.set(self.calls.get() + 1);
+            SjfScheduler::default().schedule(tasks)
+        }
+    }
+
+    #[test]
+    fn hash_is_stable() {
+        // guards against the hash changing between releases, which would orphan caches
+        assert_eq!(content_hash(&[1, 2, 3]), fnv1a(b"[1,2,3]"));
+        assert_eq!(content_hash(&"x"), 0xD47C_1617_D80F_A71F);
+    }
+
+    #[test]
+    fn key_depends_on_policy_and_workload() {
+        let tasks = srtf_textbook();
+        let sjf = run_key(&SjfScheduler::default(), &tasks).unwrap();
+
+        assert_eq!(Some(sjf), run_key(&SjfScheduler::default(), &tasks));
+        assert_ne!(Some(sjf), run_key(&SrtfScheduler::default(), &tasks));
+        assert_ne!(Some(sjf), run_key(&SjfScheduler::default(), &tasks[1..]));
+    }
+
+    #[test]
+    fn policies_holding_functions_have_no_key() {
+        let tasks = srtf_textbook();
+        let custom = SjfScheduler {
+            semantics: Semantics::Custom(TieComparator::new(|a, b| a.id.cmp(&b.id))),
+        };
+
+        assert_eq!(run_key(&DecayScheduler::default(), &tasks), None);
+        assert_eq!(run_key(&custom, &tasks), None);
+        assert!(run_key(&SjfScheduler::default(), &tasks).is_some());
+    }
+
+    #[test]
+    fn policies_without_a_key_are_not_stored() {
+        let dir = std::env::temp_dir().join(format!("fractal-nokey-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let cache = ScheduleCache::new(&dir).unwrap();
+
+        let schedule = cache
+            .schedule(&DecayScheduler::default(), srtf_textbook())
+            .unwrap();
+
+        assert_eq!(
+            schedule,
+            DecayScheduler::default().schedule(srtf_textbook())
+        );
+        assert_eq!(fs::read_dir(&dir).unwrap().count(), 0);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn repeated_runs_come_from_the_cache() {
+        let dir = std::env::temp_dir().join(format!("fractal-cache-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let cache = ScheduleCache::new(&dir).unwrap();
+        let policy = Counting::default();
+
+        let first = cache.schedule(&policy, srtf_textbook()).unwrap();
+        let second = cache.schedule(&policy, srtf_textbook()).unwrap();
+
+        assert_eq!(first, second);
+        assert_eq!(policy.calls.get(), 1);
+        assert_eq!(content_hash(&first), content_hash(&second));
+
+        // a corrupt entry is recomputed
+        for entry in fs::read_dir(&dir).unwrap() {
+            fs::write(entry.unwrap().path(), "{").unwrap();
+        }
+        assert_eq!(cache.schedule(&policy, srtf_textbook()).unwrap(), first);
+        assert_eq!(policy.calls.get(), 2);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
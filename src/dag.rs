@@ -0,0 +1,205 @@
+// Dependencies between tasks: a task only becomes eligible once everything it depends on has
+// finished.
+use crate::sim::Expired;
+use crate::Task;
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
+use std::fmt;
+
+/// Why a workload's dependencies can't be satisfied.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DependencyError {
+    /// `task` depends on an id that isn't in the workload.
+    UnknownDependency { task: u64, dependency: u64 },
+    /// The tasks, in order, each depend on the next, and the last on the first.
+    Cycle(Vec<u64>),
+}
+
+impl fmt::Display for DependencyError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            DependencyError::UnknownDependency { task, dependency } => {
+                write!(f, "task {} depends on unknown task {}", task, dependency)
+            }
+            DependencyError::Cycle(ids) => {
+                let ids: Vec<String> = ids.iter().map(|id| format!("#{}", id)).collect();
+                write!(f, "dependency cycle: {} -> {}", ids.join(" -> "), ids[0])
+            }
+        }
+    }
+}
+
+impl std::error::Error for DependencyError {}
+
+/// Checks that every dependency exists and that there are no cycles.
+pub fn validate(tasks: &[Task]) -> Result<(), DependencyError> {
+    let ids: BTreeSet<u64> = tasks.iter().map(|task| task.id).collect();
+    let mut waiting_on: BTreeMap<u64, BTreeSet<u64>> = BTreeMap::new();
+    for task in tasks {
+        for &dependency in &task.depends_on {
+            if !ids.contains(&dependency) {
+                return Err(DependencyError::UnknownDependency {
+                    task: task.id,
+                    dependency,
+                });
+            }
+        }
+        waiting_on
+            .entry(task.id)
+            .or_default()
+            .extend(task.depends_on.iter().copied());
+    }
+
+    // repeatedly retire tasks with nothing left to wait on; whatever remains is stuck
+    loop {
+        let free: Vec<u64> = waiting_on
+            .iter()
+            .filter(|(_, deps)| deps.is_empty())
+            .map(|(&id, _)| id)
+            .collect();
+        if free.is_empty() {
+            break;
+        }
+        for id in &free {
+            waiting_on.remove(id);
+        }
+        for deps in waiting_on.values_mut() {
+            for id in &free {
+                deps.remove(id);
+            }
+        }
+    }
+
+    // every stuck task waits on another stuck task, so following them must loop
+    let start = match waiting_on.keys().next() {
+        Some(&start) => start,
+        None => return Ok(()),
+    };
+    let mut path = vec![start];
+    loop {
+        let next = *waiting_on[path.last().unwrap()].iter().next().unwrap();
+        if let Some(i) = path.iter().position(|&id| id == next) {
+            return Err(DependencyError::Cycle(path.split_off(i)));
+        }
+        path.push(next);
+    }
+}
+
+/// Tasks held back until their dependencies finish, each with its submission sequence number.
+#[derive(Default)]
+pub(crate) struct Dependents {
+    unmet: BTreeMap<u64, usize>,
+    dependents: BTreeMap<u64, Vec<u64>>,
+    held: BTreeMap<u64, (u64, Task)>,
+}
+
+impl Dependents {
+    /// Numbers `tasks` in input order and splits out the ones that are ready to queue now,
+    /// holding back the rest.
+    pub(crate) fn hold(
+        tasks: Vec<Task>,
+    ) -> Result<(Vec<(u64, Task)>, Dependents), DependencyError> {
+        validate(&tasks)?;
+
+        let mut held = Dependents::default();
+        let mut ready = vec![];
+        for (seq, task) in (0..).zip(tasks) {
+            let deps: BTreeSet<u64> = task.depends_on.iter().copied().collect();
+            if deps.is_empty() {
+                ready.push((seq, task));
+                continue;
+            }
+            for &dependency in &deps {
+                held.dependents.entry(dependency).or_default().push(task.id);
+            }
+            held.unmet.insert(task.id, deps.len());
+            held.held.insert(task.id, (seq, task));
+        }
+        Ok((ready, held))
+    }
+
+    /// The tasks whose last dependency is `finished`, with their sequence numbers.
+    pub(crate) fn release(&mut self, finished: u64) -> Vec<(u64, Task)> {
+        let mut released = vec![];
+        for id in self.dependents.remove(&finished).unwrap_or_default() {
+            let count = self.unmet.get_mut(&id).unwrap();
+            *count -= 1;
+            if *count == 0 {
+                released.push(self.held.remove(&id).unwrap());
+            }
+        }
+        released
+    }
+
+    /// The held tasks that can now never run because something they depend on, directly or
+    /// not, gave up waiting in `expired` instead of finishing. Each gives up when the task it
+    /// was waiting on did, in that order.
+    pub(crate) fn stranded(mut self, expired: &[Expired]) -> Vec<Expired> {
+        let mut stranded = vec![];
+        let mut gave_up: VecDeque<Expired> = expired.iter().copied().collect();
+        while let Some(Expired { id, at }) = gave_up.pop_front() {
+            for dependent in self.dependents.remove(&id).unwrap_or_default() {
+                if self.held.remove(&dependent).is_some() {
+                    let expired = Expired { id: dependent, at };
+                    stranded.push(expired);
+                    gave_up.push_back(expired);
+                }
+            }
+        }
+        stranded.sort_by_key(|expired| expired.at);
+        stranded
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn task(id: u64, depends_on: Vec<u64>) -> Task {
+        Task {
+            id,
+            execution_duration: 1,
+            depends_on,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn acyclic_graph_is_valid() {
+        let tasks = vec![task(1, vec![]), task(2, vec![1]), task(3, vec![1, 2])];
+
+        assert_eq!(validate(&tasks), Ok(()));
+    }
+
+    #[test]
+    fn cycle_is_reported_in_dependency_order() {
+        // #4 is stuck behind the cycle but isn't part of it
+        let tasks = vec![
+            task(1, vec![]),
+            task(2, vec![3]),
+            task(3, vec![5]),
+            task(4, vec![2]),
+            task(5, vec![2, 1]),
+        ];
+
+        let error = validate(&tasks).unwrap_err();
+
+        assert_eq!(error, DependencyError::Cycle(vec![2, 3, 5]));
+        assert_eq!(error.to_string(), "dependency cycle: #2 -> #3 -> #5 -> #2");
+    }
+
+    #[test]
+    fn self_dependency_is_a_cycle() {
+        assert_eq!(
+            validate(&[task(7, vec![7])]),
+            Err(DependencyError::Cycle(vec![7]))
+        );
+    }
+
+    #[test]
+    fn unknown_dependency() {
+        assert_eq!(
+            validate(&[task(1, vec![9])]).unwrap_err().to_string(),
+            "task 1 depends on unknown task 9"
+        );
+    }
+}
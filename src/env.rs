@@ -0,0 +1,284 @@
+// A step-based environment around the single-CPU simulator, in the style of OpenAI Gym, so that
+// reinforcement-learning agents can act as the scheduling policy.
+use crate::scheduler::Unsupported;
+use crate::{PolicyCapabilities, Task};
+use std::fmt;
+
+/// What the agent is rewarded for. Rewards are negative costs, so higher is better.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Objective {
+    /// Reward is minus the chosen task's wait (time from ready to start).
+    MinimizeWait,
+    /// Reward is minus the chosen task's turnaround (time from queued to finish).
+    MinimizeTurnaround,
+    /// Reward is minus the time the clock advanced, including any idle time afterwards.
+    MinimizeMakespan,
+}
+
+/// A task as the agent sees it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ReadyTask {
+    pub id: u64,
+    pub queued_at: u32,
+    pub execution_duration: u32,
+    /// How long it has been ready, as of the current time.
+    pub waited: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Observation {
+    pub time: u32,
+    /// Tasks that may be dispatched now, in id order.
+    pub ready: Vec<ReadyTask>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Step {
+    pub observation: Observation,
+    pub reward: f64,
+    /// Whether every task has run; no further steps are possible until `reset`.
+    pub done: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StepError {
+    /// Every task has already run.
+    Done,
+    /// The chosen task is not in the ready set.
+    NotReady(u64),
+}
+
+impl fmt::Display for StepError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            StepError::Done => write!(f, "every task has already run"),
+            StepError::NotReady(id) => write!(f, "task {} is not ready to run", id),
+        }
+    }
+}
+
+impl std::error::Error for StepError {}
+
+/// Each step the agent picks one ready task, which runs to completion; the clock then moves on
+/// to the task's finish (or, if nothing is ready by then, to the next arrival).
+///
+/// Only a task's `queued_at`, `not_before` and `execution_duration` shape the episode. The
+/// agent may act on its priority, deadline or tag, but reservations, abandonment, dependencies,
+/// fillers, weights and cold starts aren't modelled, so `new` rejects workloads that use them.
+pub struct Environment {
+    workload: Vec<Task>,
+    objective: Objective,
+    time: u32,
+    pending: Vec<Task>,
+    ready: Vec<Task>,
+}
+
+impl Environment {
+    /// Fails on the first task using a field the environment can't honour.
+    pub fn new(workload: Vec<Task>, objective: Objective) -> Result<Self, Unsupported> {
+        Environment::capabilities().check(&workload)?;
+        let mut env = Environment {
+            workload,
+            objective,
+            time: 0,
+            pending: vec![],
+            ready: vec![],
+        };
+        env.reset();
+        Ok(env)
+    }
+
+    /// What the environment honours, leaving the choice of task to the agent.
+    pub fn capabilities() -> PolicyCapabilities {
+        PolicyCapabilities {
+            priorities: true,
+            deadlines: true,
+            tags: true,
+            deterministic: true,
+            ..Default::default()
+        }
+    }
+
+    /// Restarts the episode from the beginning of the workload.
+    pub fn reset(&mut self) -> Observation {
+        self.time = 0;
+        self.pending = self.workload.clone();
+        self.pending.sort_by_key(|task| task.ready_at());
+        self.ready.clear();
+        self.advance();
+        self.observe()
+    }
+
+    pub fn observe(&self) -> Observation {
+        let mut ready: Vec<ReadyTask> = self
+            .ready
+            .iter()
+            .map(|task| ReadyTask {
+                id: task.id,
+                queued_at: task.queued_at,
+                execution_duration: task.execution_duration,
+                waited: self.time - task.ready_at(),
+            })
+            .collect();
+        ready.sort_by_key(|task| task.id);
+
+        Observation {
+            time: self.time,
+            ready,
+        }
+    }
+
+    pub fn is_done(&self) -> bool {
+        self.pending.is_empty() && self.ready.is_empty()
+    }
+
+    /// Runs the ready task `id` to completion.
+    pub fn step(&mut self, id: u64) -> Result<Step, StepError> {
+        if self.is_done() {
+            return Err(StepError::Done);
+        }
+        let index = self
+            .ready
+            .iter()
+            .position(|task| task.id == id)
+            .ok_or(StepError::NotReady(id))?;
+        let task = self.ready.remove(index);
+
+        let started_at = self.time;
+        self.time += task.execution_duration;
+        self.advance();
+
+        let cost = match self.objective {
+            Objective::MinimizeWait => started_at - task.ready_at(),
+            Objective::MinimizeTurnaround => started_at + task.execution_duration - task.queued_at,
+            Objective::MinimizeMakespan => self.time - started_at,
+        };
+
+        Ok(Step {
+            observation: self.observe(),
+            reward: -f64::from(cost),
+            done: self.is_done(),
+        })
+    }
+
+    /// Moves newly ready tasks into the ready set, skipping ahead over idle time.
+    fn advance(&mut self) {
+        if self.ready.is_empty() {
+            if let Some(task) = self.pending.first() {
+                self.time = self.time.max(task.ready_at());
+            }
+        }
+        let index = self
+            .pending
+            .partition_point(|task| task.ready_at() <= self.time);
+        self.ready.extend(self.pending.drain(..index));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn workload() -> Vec<Task> {
+        vec![
+            Task {
+                id: 42,
+                queued_at: 1,
+                execution_duration: 3,
+                ..Default::default()
+            },
+            Task {
+                id: 43,
+                queued_at: 2,
+                execution_duration: 3,
+                ..Default::default()
+            },
+            Task {
+                id: 44,
+                queued_at: 1,
+                execution_duration: 2,
+                ..Default::default()
+            },
+            Task {
+                id: 45,
+                queued_at: 20,
+                execution_duration: 1,
+                ..Default::default()
+            },
+        ]
+    }
+
+    #[test]
+    fn episode_runs_chosen_tasks() {
+        let mut env = Environment::new(workload(), Objective::MinimizeWait).unwrap();
+
+        let observation = env.observe();
+        assert_eq!(observation.time, 1);
+        let ready: Vec<u64> = observation.ready.iter().map(|t| t.id).collect();
+        assert_eq!(ready, vec![42, 44]);
+
+        let step = env.step(42).unwrap();
+        assert_eq!(step.reward, 0.0);
+        assert_eq!(step.observation.time, 4);
+        assert_eq!(step.observation.ready.len(), 2);
+        assert!(!step.done);
+
+        // 44 has been ready since 1
+        let step = env.step(44).unwrap();
+        assert_eq!(step.reward, -3.0);
+
+        // 43 has been ready since 2; afterwards the clock skips to 45's arrival
+        let step = env.step(43).unwrap();
+        assert_eq!(step.reward, -4.0);
+        assert_eq!(step.observation.time, 20);
+
+        let step = env.step(45).unwrap();
+        assert!(step.done);
+        assert_eq!(env.step(45), Err(StepError::Done));
+    }
+
+    #[test]
+    fn invalid_action_is_rejected() {
+        let mut env = Environment::new(workload(), Objective::MinimizeWait).unwrap();
+
+        assert_eq!(env.step(43), Err(StepError::NotReady(43)));
+        assert_eq!(env.step(100), Err(StepError::NotReady(100)));
+    }
+
+    #[test]
+    fn reset_restarts_episode() {
+        let mut env = Environment::new(workload(), Objective::MinimizeMakespan).unwrap();
+        let initial = env.observe();
+
+        // running 44 takes 2 seconds
+        assert_eq!(env.step(44).unwrap().reward, -2.0);
+
+        assert_eq!(env.reset(), initial);
+    }
+
+    #[test]
+    fn unmodelled_fields_are_rejected() {
+        for (index, field) in [(0, "reserved_at"), (1, "give_up_after"), (3, "depends_on")] {
+            let mut workload = workload();
+            match field {
+                "reserved_at" => workload[index].reserved_at = Some(5),
+                "give_up_after" => workload[index].give_up_after = Some(1),
+                _ => workload[index].depends_on = vec![42],
+            }
+            let id = workload[index].id;
+
+            assert_eq!(
+                Environment::new(workload, Objective::MinimizeWait).err(),
+                Some(Unsupported { id, field })
+            );
+        }
+    }
+
+    #[test]
+    fn priorities_are_left_to_the_agent() {
+        let mut workload = workload();
+        workload[1].priority = Some(9);
+
+        assert!(Environment::new(workload, Objective::MinimizeWait).is_ok());
+    }
+}
@@ -0,0 +1,223 @@
+// The full event log of a schedule, for auditing a run or driving a visualization.
+use crate::scheduler::{Schedule, ScheduleEvent};
+
+/// Something that happened during a run.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Event {
+    /// Task `id` was queued.
+    Queued { time: u32, id: u64 },
+    /// Task `id` first got a CPU.
+    Started { time: u32, id: u64, core: usize },
+    /// Task `id` was taken off its CPU so task `by` could run.
+    Preempted { time: u32, id: u64, by: u64 },
+    /// Task `id`, previously preempted, got a CPU back.
+    Resumed { time: u32, id: u64 },
+    /// Task `id` completed.
+    Finished { time: u32, id: u64, core: usize },
+    /// `core` sat idle from `time` until `until`, between two tasks.
+    Idle { time: u32, core: usize, until: u32 },
+}
+
+impl Event {
+    pub fn time(&self) -> u32 {
+        match *self {
+            Event::Queued { time, .. }
+            | Event::Started { time, .. }
+            | Event::Preempted { time, .. }
+            | Event::Resumed { time, .. }
+            | Event::Finished { time, .. }
+            | Event::Idle { time, .. } => time,
+        }
+    }
+
+    /// The task the event is about; `None` for `Idle`.
+    pub fn id(&self) -> Option<u64> {
+        match *self {
+            Event::Queued { id, .. }
+            | Event::Started { id, .. }
+            | Event::Preempted { id, .. }
+            | Event::Resumed { id, .. }
+            | Event::Finished { id, .. } => Some(id),
+            Event::Idle { .. } => None,
+        }
+    }
+
+    /// Orders events at the same instant: a CPU is freed, then arrivals, then dispatch.
+    fn rank(&self) -> u8 {
+        match self {
+            Event::Finished { .. } => 0,
+            Event::Preempted { .. } => 1,
+            Event::Idle { .. } => 2,
+            Event::Queued { .. } => 3,
+            Event::Started { .. } => 4,
+            Event::Resumed { .. } => 5,
+        }
+    }
+}
+
+/// Every event in `schedule`, in time order.
+pub fn log(schedule: &Schedule) -> Vec<Event> {
+    let core_at = |id: u64, at: u32, starting: bool| {
+        schedule
+            .segments
+            .iter()
+            .find(|s| s.id == id && if starting { s.start == at } else { s.end == at })
+            .map_or(0, |s| s.core)
+    };
+
+    let mut events = vec![];
+    for run in &schedule.runs {
+        let id = run.task.id;
+        events.push(Event::Queued {
+            time: run.task.queued_at,
+            id,
+        });
+        events.push(Event::Started {
+            time: run.started_at,
+            id,
+            core: core_at(id, run.started_at, true),
+        });
+        events.push(Event::Finished {
+            time: run.finished_at,
+            id,
+            core: core_at(id, run.finished_at, false),
+        });
+    }
+    events.extend(schedule.events.iter().map(|event| match *event {
+        ScheduleEvent::Preempted { time, id, by } => Event::Preempted { time, id, by },
+        ScheduleEvent::Resumed { time, id } => Event::Resumed { time, id },
+    }));
+    for (core, timeline) in schedule.timelines().iter().enumerate() {
+        for pair in timeline.windows(2) {
+            if pair[0].end < pair[1].start {
+                events.push(Event::Idle {
+                    time: pair[0].end,
+                    core,
+                    until: pair[1].start,
+                });
+            }
+        }
+    }
+
+    events.sort_by_key(|event| (event.time(), event.rank()));
+    events
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{execution_schedule, Scheduler, SrtfScheduler, Task};
+
+    #[test]
+    fn non_preemptive_log() {
+        let tasks = vec![
+            Task {
+                id: 42,
+                queued_at: 0,
+                execution_duration: 2,
+                ..Default::default()
+            },
+            Task {
+                id: 43,
+                queued_at: 5,
+                execution_duration: 1,
+                ..Default::default()
+            },
+        ];
+
+        let events = log(&execution_schedule(tasks));
+
+        assert_eq!(
+            events,
+            vec![
+                Event::Queued { time: 0, id: 42 },
+                Event::Started {
+                    time: 0,
+                    id: 42,
+                    core: 0,
+                },
+                Event::Finished {
+                    time: 2,
+                    id: 42,
+                    core: 0,
+                },
+                Event::Idle {
+                    time: 2,
+                    core: 0,
+                    until: 5,
+                },
+                Event::Queued { time: 5, id: 43 },
+                Event::Started {
+                    time: 5,
+                    id: 43,
+                    core: 0,
+                },
+                Event::Finished {
+                    time: 6,
+                    id: 43,
+                    core: 0,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn preemptions_are_interleaved() {
+        let tasks = vec![
+            Task {
+                id: 1,
+                queued_at: 0,
+                execution_duration: 4,
+                ..Default::default()
+            },
+            Task {
+                id: 2,
+                queued_at: 1,
+                execution_duration: 1,
+                ..Default::default()
+            },
+        ];
+
+        let events = log(&SrtfScheduler::default().schedule(tasks));
+
+        let kinds: Vec<(u32, Option<u64>)> = events.iter().map(|e| (e.time(), e.id())).collect();
+        assert_eq!(
+            kinds,
+            vec![
+                (0, Some(1)),
+                (0, Some(1)),
+                (1, Some(1)),
+                (1, Some(2)),
+                (1, Some(2)),
+                (2, Some(2)),
+                (2, Some(1)),
+                (5, Some(1)),
+            ]
+        );
+        assert_eq!(
+            events[2],
+            Event::Preempted {
+                time: 1,
+                id: 1,
+                by: 2,
+            }
+        );
+        assert_eq!(events[6], Event::Resumed { time: 2, id: 1 });
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn events_serialize_by_kind() {
+        let event = Event::Preempted {
+            time: 1,
+            id: 1,
+            by: 2,
+        };
+
+        let json = serde_json::to_string(&event).unwrap();
+
+        assert_eq!(json, r#"{"Preempted":{"time":1,"id":1,"by":2}}"#);
+        assert_eq!(serde_json::from_str::<Event>(&json).unwrap(), event);
+    }
+}
@@ -0,0 +1,122 @@
+// Built-in workloads, so tests, demos and docs can share the same canonical data.
+use crate::Task;
+
+/// A named workload with a note on where it comes from or what it exercises.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Workload {
+    pub name: &'static str,
+    pub description: &'static str,
+    pub tasks: Vec<Task>,
+}
+
+/// Every built-in workload.
+pub fn workloads() -> Vec<Workload> {
+    vec![
+        Workload {
+            name: "sjf-textbook",
+            description: "Four jobs queued at once; SJF beats FCFS on average wait",
+            tasks: sjf_textbook(),
+        },
+        Workload {
+            name: "srtf-textbook",
+            description: "Staggered arrivals where preempting for shorter work pays off",
+            tasks: srtf_textbook(),
+        },
+        Workload {
+            name: "bursty-web",
+            description: "Bursts of short requests with the odd long batch job in between",
+            tasks: bursty_web(),
+        },
+        Workload {
+            name: "dag-pipeline",
+            description: "Extract, three parallel transforms, then a load that needs them all",
+            tasks: dag_pipeline(),
+        },
+    ]
+}
+
+/// The built-in workload called `name`, if there is one.
+pub fn workload(name: &str) -> Option<Workload> {
+    workloads().into_iter().find(|w| w.name == name)
+}
+
+fn tasks(rows: &[(u64, u32, u32)]) -> Vec<Task> {
+    rows.iter()
+        .map(|&(id, queued_at, execution_duration)| Task {
+            id,
+            queued_at,
+            execution_duration,
+            ..Default::default()
+        })
+        .collect()
+}
+
+/// The classic SJF example: bursts of 6, 8, 7 and 3, all queued at 0.
+pub fn sjf_textbook() -> Vec<Task> {
+    tasks(&[(1, 0, 6), (2, 0, 8), (3, 0, 7), (4, 0, 3)])
+}
+
+/// The classic SRTF example: bursts of 8, 4, 9 and 5 arriving a second apart.
+pub fn srtf_textbook() -> Vec<Task> {
+    tasks(&[(1, 0, 8), (2, 1, 4), (3, 2, 9), (4, 3, 5)])
+}
+
+/// Five bursts, 20 apart, of eight one- or two-second requests, with a 15 second batch job
+/// queued just after every other burst.
+pub fn bursty_web() -> Vec<Task> {
+    let mut rows = vec![];
+    for burst in 0..5_u32 {
+        let at = burst * 20;
+        for i in 0..8 {
+            let id = u64::from(burst * 10 + i);
+            rows.push((id, at + i / 4, 1 + i % 2));
+        }
+        if burst % 2 == 0 {
+            rows.push((100 + u64::from(burst), at + 3, 15));
+        }
+    }
+    tasks(&rows)
+}
+
+/// An ETL pipeline: #1 extracts, #2-#4 transform its output, and #5 loads once they are done.
+pub fn dag_pipeline() -> Vec<Task> {
+    let mut tasks = tasks(&[(1, 0, 4), (2, 0, 3), (3, 0, 6), (4, 0, 2), (5, 0, 1)]);
+    for task in &mut tasks[1..4] {
+        task.depends_on = vec![1];
+    }
+    tasks[4].depends_on = vec![2, 3, 4];
+    tasks
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dag::validate;
+    use crate::execution_order;
+    use std::collections::BTreeSet;
+
+    #[test]
+    fn names_and_ids_are_unique() {
+        let all = workloads();
+        let names: BTreeSet<&str> = all.iter().map(|w| w.name).collect();
+        assert_eq!(names.len(), all.len());
+
+        for w in &all {
+            let ids: BTreeSet<u64> = w.tasks.iter().map(|t| t.id).collect();
+            assert_eq!(ids.len(), w.tasks.len(), "{}", w.name);
+            assert_eq!(validate(&w.tasks), Ok(()), "{}", w.name);
+        }
+    }
+
+    #[test]
+    fn lookup_by_name() {
+        assert_eq!(workload("sjf-textbook").unwrap().tasks, sjf_textbook());
+        assert_eq!(workload("nope"), None);
+    }
+
+    #[test]
+    fn pipeline_runs_in_stages() {
+        // #4 is the shortest transform, but nothing starts before the extract
+        assert_eq!(execution_order(dag_pipeline()), vec![1, 4, 2, 3, 5]);
+    }
+}
@@ -0,0 +1,223 @@
+// CSV import of tasks and export of schedules, e.g. to round-trip data through a spreadsheet.
+use super::quantize::{QuantizationReport, Quantizer};
+use crate::{Schedule, Task};
+use std::convert::TryFrom;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+const TASK_COLUMNS: [&str; 3] = ["id", "queued_at", "execution_duration"];
+
+/// Parses tasks from CSV with a header row naming at least `id`, `queued_at` and
+/// `execution_duration`, in any order. Other columns are ignored, as are blank lines.
+pub fn parse_tasks(csv: &str) -> io::Result<Vec<Task>> {
+    parse_with(csv, |value, column| {
+        let value: u64 = value
+            .parse()
+            .map_err(|_| format!("`{}` is not a valid {}", value, column))?;
+        u32::try_from(value).map_err(|_| format!("{} is too large", value))
+    })
+}
+
+/// Like `parse_tasks`, but `queued_at` and `execution_duration` may be fractional, and are
+/// converted to whole ticks by `quantizer`. Also reports how much that moved them.
+pub fn parse_tasks_quantized(
+    csv: &str,
+    quantizer: Quantizer,
+) -> io::Result<(Vec<Task>, QuantizationReport)> {
+    let mut report = QuantizationReport::default();
+    let tasks = parse_with(csv, |value, column| {
+        let (ticks, error) = value
+            .parse()
+            .ok()
+            .and_then(|value| quantizer.quantize(value))
+            .ok_or_else(|| format!("`{}` is not a valid {}", value, column))?;
+        report.record(error);
+        Ok(ticks)
+    })?;
+    Ok((tasks, report))
+}
+
+/// Parses tasks, converting each time field with `time`, which is given the field and its
+/// column name.
+fn parse_with<F>(csv: &str, mut time: F) -> io::Result<Vec<Task>>
+where
+    F: FnMut(&str, &str) -> Result<u32, String>,
+{
+    let invalid = |message: String| io::Error::new(io::ErrorKind::InvalidData, message);
+
+    let mut lines = csv
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty());
+    let header: Vec<&str> = match lines.next() {
+        Some((_, header)) => header.split(',').map(str::trim).collect(),
+        None => return Ok(vec![]),
+    };
+    let mut columns = [0; 3];
+    for (column, name) in columns.iter_mut().zip(TASK_COLUMNS.iter()) {
+        *column = header
+            .iter()
+            .position(|h| h == name)
+            .ok_or_else(|| invalid(format!("missing column `{}`", name)))?;
+    }
+
+    lines
+        .map(|(number, line)| {
+            let fields: Vec<&str> = line.split(',').map(str::trim).collect();
+            let field = |i: usize| -> io::Result<&str> {
+                fields.get(columns[i]).copied().ok_or_else(|| {
+                    invalid(format!(
+                        "line {}: missing `{}`",
+                        number + 1,
+                        TASK_COLUMNS[i]
+                    ))
+                })
+            };
+            let id = field(0)?;
+            let id = id.parse().map_err(|_| {
+                invalid(format!(
+                    "line {}: `{}` is not a valid {}",
+                    number + 1,
+                    id,
+                    TASK_COLUMNS[0]
+                ))
+            })?;
+            let mut time_in = |i: usize| -> io::Result<u32> {
+                time(field(i)?, TASK_COLUMNS[i])
+                    .map_err(|message| invalid(format!("line {}: {}", number + 1, message)))
+            };
+
+            Ok(Task {
+                id,
+                queued_at: time_in(1)?,
+                execution_duration: time_in(2)?,
+                ..Default::default()
+            })
+        })
+        .collect()
+}
+
+/// Reads tasks from a CSV file; see `parse_tasks`.
+pub fn load_tasks<P: AsRef<Path>>(path: P) -> io::Result<Vec<Task>> {
+    parse_tasks(&fs::read_to_string(path)?)
+}
+
+/// One row per task in completion order, with the task's columns followed by when it started,
+/// finished and how long it waited.
+pub fn schedule_to_csv(schedule: &Schedule) -> String {
+    let mut csv =
+        String::from("id,queued_at,execution_duration,started_at,finished_at,wait_time\n");
+    for run in &schedule.runs {
+        csv.push_str(&format!(
+            "{},{},{},{},{},{}\n",
+            run.task.id,
+            run.task.queued_at,
+            run.task.execution_duration,
+            run.started_at,
+            run.finished_at,
+            run.wait_time
+        ));
+    }
+    csv
+}
+
+/// Writes `schedule` to a CSV file; see `schedule_to_csv`.
+pub fn save_schedule<P: AsRef<Path>>(path: P, schedule: &Schedule) -> io::Result<()> {
+    fs::write(path, schedule_to_csv(schedule))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::execution_schedule;
+    use crate::io::quantize::Rounding;
+
+    #[test]
+    fn tasks_in_any_column_order() {
+        let tasks = parse_tasks(
+            "queued_at, id, note, execution_duration\n\
+             0, 42, first, 3\n\
+             \n\
+             1, 43, second, 2\n",
+        )
+        .unwrap();
+
+        assert_eq!(tasks.len(), 2);
+        assert_eq!(
+            (tasks[1].id, tasks[1].queued_at, tasks[1].execution_duration),
+            (43, 1, 2)
+        );
+        assert!(parse_tasks("").unwrap().is_empty());
+    }
+
+    #[test]
+    fn fractional_times_are_quantized() {
+        let csv = "id,queued_at,execution_duration\n1,0.4,1.5\n2,1.25,0.1\n";
+
+        let (tasks, report) =
+            parse_tasks_quantized(csv, Quantizer::new(1.0, Rounding::Up)).unwrap();
+
+        let times: Vec<(u32, u32)> = tasks
+            .iter()
+            .map(|t| (t.queued_at, t.execution_duration))
+            .collect();
+        assert_eq!(times, vec![(1, 2), (2, 1)]);
+        assert_eq!(report.values, 4);
+        assert!((report.total_error - (0.6 + 0.5 + 0.75 + 0.9)).abs() < 1e-9);
+        assert!((report.max_error - 0.9).abs() < 1e-9);
+        assert_eq!(
+            parse_tasks_quantized(
+                "id,queued_at,execution_duration\n1,-2,1\n",
+                Quantizer::new(1.0, Rounding::Up)
+            )
+            .unwrap_err()
+            .to_string(),
+            "line 2: `-2` is not a valid queued_at"
+        );
+    }
+
+    #[test]
+    fn bad_rows_name_the_line() {
+        let error = |csv: &str| parse_tasks(csv).unwrap_err().to_string();
+
+        assert_eq!(
+            error("id,queued_at\n1,0\n"),
+            "missing column `execution_duration`"
+        );
+        assert_eq!(
+            error("id,queued_at,execution_duration\n1,0,3\n2,soon,3\n"),
+            "line 3: `soon` is not a valid queued_at"
+        );
+        assert_eq!(
+            error("id,queued_at,execution_duration\n1,0\n"),
+            "line 2: missing `execution_duration`"
+        );
+        assert_eq!(
+            error("id,queued_at,execution_duration\n1,0,5000000000\n"),
+            "line 2: 5000000000 is too large"
+        );
+    }
+
+    #[test]
+    fn schedule_round_trips_through_a_file() {
+        let path = std::env::temp_dir().join("fractal-interview-schedule.csv");
+        let tasks =
+            parse_tasks("id,queued_at,execution_duration\n42,0,3\n43,1,2\n44,2,1\n").unwrap();
+        let schedule = execution_schedule(tasks.clone());
+
+        save_schedule(&path, &schedule).unwrap();
+
+        assert_eq!(
+            fs::read_to_string(&path).unwrap(),
+            "id,queued_at,execution_duration,started_at,finished_at,wait_time\n\
+             42,0,3,0,3,0\n\
+             44,2,1,3,4,1\n\
+             43,1,2,4,6,3\n"
+        );
+        // the extra columns are ignored on the way back in
+        let mut reloaded = load_tasks(&path).unwrap();
+        reloaded.sort_by_key(|task| task.id);
+        assert_eq!(reloaded, tasks);
+    }
+}
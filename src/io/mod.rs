@@ -0,0 +1,3 @@
+// Reading workloads from, and writing schedules to, formats other tools understand.
+pub mod csv;
+pub mod quantize;
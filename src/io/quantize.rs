@@ -0,0 +1,111 @@
+// Converting fractional real-world times onto the simulator's integer clock.
+
+/// Which way a time between two ticks goes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Rounding {
+    /// To the nearest tick, halves away from zero.
+    #[default]
+    Nearest,
+    /// Up to the next tick, so durations are never underestimated.
+    Up,
+    /// Down to the previous tick.
+    Down,
+}
+
+/// Maps times measured in some unit (seconds, say) to whole ticks of `tick` of that unit.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Quantizer {
+    pub tick: f64,
+    pub rounding: Rounding,
+}
+
+impl Quantizer {
+    /// Panics unless `tick` is positive and finite.
+    pub fn new(tick: f64, rounding: Rounding) -> Self {
+        assert!(
+            tick > 0.0 && tick.is_finite(),
+            "tick must be positive and finite"
+        );
+        Quantizer { tick, rounding }
+    }
+
+    /// `value` in whole ticks, with the error that introduces in `value`'s units (positive if
+    /// rounded up). `None` if `value` is negative, not a number, or too many ticks for a `u32`.
+    pub fn quantize(&self, value: f64) -> Option<(u32, f64)> {
+        if value.is_nan() || value < 0.0 {
+            return None;
+        }
+        let exact = value / self.tick;
+        let nearest = exact.round();
+        // values that are a whole number of ticks up to float noise (0.3 / 0.1, say) stay put
+        let ticks = if (exact - nearest).abs() < 1e-9 {
+            nearest
+        } else {
+            match self.rounding {
+                Rounding::Nearest => nearest,
+                Rounding::Up => exact.ceil(),
+                Rounding::Down => exact.floor(),
+            }
+        };
+        if ticks > f64::from(u32::MAX) {
+            return None;
+        }
+        Some((ticks as u32, ticks * self.tick - value))
+    }
+}
+
+/// How much quantizing a batch of values moved them, in the input's units.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct QuantizationReport {
+    /// How many values were quantized.
+    pub values: usize,
+    /// Sum of the signed errors: how far totals have drifted, e.g. total work.
+    pub net_error: f64,
+    /// Sum of the absolute errors.
+    pub total_error: f64,
+    pub max_error: f64,
+}
+
+impl QuantizationReport {
+    pub(crate) fn record(&mut self, error: f64) {
+        self.values += 1;
+        self.net_error += error;
+        self.total_error += error.abs();
+        self.max_error = self.max_error.max(error.abs());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rounds_to_the_tick_in_the_chosen_direction() {
+        let ticks = |rounding, value| Quantizer::new(0.25, rounding).quantize(value).unwrap().0;
+
+        assert_eq!(ticks(Rounding::Nearest, 1.1), 4);
+        assert_eq!(ticks(Rounding::Up, 1.01), 5);
+        assert_eq!(ticks(Rounding::Down, 1.24), 4);
+        // already on a tick, despite 0.3 / 0.1 being slightly under 3
+        assert_eq!(
+            Quantizer::new(0.1, Rounding::Down).quantize(0.3).unwrap().0,
+            3
+        );
+    }
+
+    #[test]
+    fn reports_the_error() {
+        let (ticks, error) = Quantizer::new(1.0, Rounding::Up).quantize(2.25).unwrap();
+
+        assert_eq!((ticks, error), (3, 0.75));
+    }
+
+    #[test]
+    fn rejects_unrepresentable_values() {
+        let q = Quantizer::new(0.001, Rounding::Nearest);
+
+        assert_eq!(q.quantize(-1.0), None);
+        assert_eq!(q.quantize(f64::NAN), None);
+        assert_eq!(q.quantize(5e6), None);
+    }
+}
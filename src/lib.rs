@@ -0,0 +1,481 @@
+// We need a function that will evaluate the order that a set of tasks will be completed in.
+// When idle, the CPU will take the next task that has been queued with the lowest time to complete.
+
+// queued by moment in time
+// keep CPU busy for exec duration
+// seconds
+// one task at a time
+
+#[cfg(feature = "serde")]
+pub mod cache;
+pub mod dag;
+pub mod env;
+pub mod events;
+pub mod examples;
+pub mod io;
+pub mod metrics;
+pub mod online;
+pub mod regression;
+mod rng;
+pub mod scheduler;
+pub mod sim;
+pub mod sketch;
+pub mod stats;
+mod task;
+pub mod viz;
+pub mod workload;
+
+pub use online::SimScheduler;
+pub use scheduler::{
+    AgingScheduler, BatchScheduler, CostOfDelayScheduler, CreditScheduler, DecayScheduler,
+    EdfScheduler, FairScheduler, FillerScheduler, LotteryScheduler, MlfqScheduler,
+    MultiCoreScheduler, PartitionedScheduler, PolicyCapabilities, PriorityScheduler,
+    RoundRobinScheduler, Schedule, ScheduleError, ScheduledTask, Scheduler, Semantics,
+    SjfScheduler, SrtfScheduler, SwitchingScheduler,
+};
+pub use sim::Projection;
+pub use task::{DeadlineKind, Task};
+
+/// When each task runs under non-preemptive SJF, with its wait and the task itself.
+pub fn execution_schedule(tasks: Vec<Task>) -> Schedule {
+    SjfScheduler::default().schedule(tasks)
+}
+
+/// Like `execution_schedule`, but rejects workloads it can't schedule correctly (duplicate ids,
+/// dependency cycles, times past `u32::MAX`) instead of panicking or producing wrong times.
+pub fn try_execution_order(tasks: Vec<Task>) -> Result<Schedule, ScheduleError> {
+    scheduler::validate(&tasks)?;
+    Ok(execution_schedule(tasks))
+}
+
+pub fn execution_order(tasks: Vec<Task>) -> Vec<u64> {
+    execution_schedule(tasks).order()
+}
+
+/// Like `execution_order`, with ties between equal-length tasks broken according to `semantics`.
+pub fn execution_order_with(tasks: Vec<Task>, semantics: Semantics) -> Vec<u64> {
+    SjfScheduler { semantics }.schedule(tasks).order()
+}
+
+/// The runs of `execution_schedule(tasks)` one at a time in completion order, simulated only as
+/// far as the iterator is consumed. Every task is queued up front, but taking the first few
+/// completions of a large workload doesn't simulate the rest.
+///
+/// Panics if a dependency is unknown or the dependencies form a cycle.
+pub fn execution_order_iter(tasks: Vec<Task>) -> impl Iterator<Item = ScheduledTask> {
+    SjfScheduler::default().runs(tasks)
+}
+
+pub fn execution_order_original(mut tasks: Vec<Task>) -> Vec<u64> {
+    if tasks.is_empty() {
+        return vec![];
+    }
+
+    tasks.sort_by_key(|task| task.queued_at);
+
+    let mut time = 0_u32;
+    let mut result: Vec<u64> = vec![];
+
+    loop {
+        let current_task = tasks
+            .iter()
+            .take_while(|task| task.queued_at <= time)
+            .min_by_key(|task| task.execution_duration);
+
+        if let Some(current_task) = current_task {
+            time += current_task.execution_duration;
+            result.push(current_task.id);
+
+            if tasks.is_empty() {
+                break;
+            }
+
+            let index = tasks
+                .iter()
+                .position(|task| task.id == current_task.id)
+                .unwrap();
+            tasks.remove(index);
+        } else if !tasks.is_empty() {
+            time = tasks[0].queued_at;
+        } else {
+            break;
+        }
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scheduler::TieComparator;
+
+    #[test]
+    fn reverse_queue_order() {
+        // 44   0   2
+        // 43   2   3
+        // 42   5   3
+
+        let tasks = vec![
+            Task {
+                id: 42,
+                queued_at: 5,
+                execution_duration: 3,
+                ..Default::default()
+            },
+            Task {
+                id: 43,
+                queued_at: 2,
+                execution_duration: 3,
+                ..Default::default()
+            },
+            Task {
+                id: 44,
+                queued_at: 0,
+                execution_duration: 2,
+                ..Default::default()
+            },
+        ];
+
+        assert_eq!(execution_order(tasks), vec![44, 43, 42]);
+    }
+
+    #[test]
+    fn two_items_queued_at_once() {
+        // 0: #42 is queued
+        // 0: #42 is started
+        // 1: #43 is queued
+        // 2: #44 is queued
+        // 3: #42 is finished
+        // 3: #44 is started (it is queued and has a lower execution_duration than #43)
+        // 5: #44 is finished
+        // 5: #43 is started
+        // 8: #43 is finished
+
+        let tasks = vec![
+            Task {
+                id: 42,
+                queued_at: 0,
+                execution_duration: 3,
+                ..Default::default()
+            },
+            Task {
+                id: 43,
+                queued_at: 1,
+                execution_duration: 3,
+                ..Default::default()
+            },
+            Task {
+                id: 44,
+                queued_at: 2,
+                execution_duration: 2,
+                ..Default::default()
+            },
+        ];
+
+        assert_eq!(execution_order(tasks), vec![42, 44, 43]);
+    }
+
+    #[test]
+    fn idle() {
+        // 0: #42 is queued
+        // 0: #42 is started
+        // 1: #43 is queued
+        // 2: #44 is queued
+        // 3: #42 is finished
+        // 3: #44 is started (it is queued and has a lower execution_duration than #43)
+        // 5: #44 is finished
+        // 5: #43 is started
+        // 8: #43 is finished
+
+        let tasks = vec![
+            Task {
+                id: 42,
+                queued_at: 0,
+                execution_duration: 1,
+                ..Default::default()
+            },
+            Task {
+                id: 43,
+                queued_at: 3,
+                execution_duration: 3,
+                ..Default::default()
+            },
+        ];
+
+        assert_eq!(execution_order(tasks), vec![42, 43]);
+    }
+
+    #[test]
+    fn empty_task_list() {
+        assert_eq!(execution_order(vec![]), Vec::<u64>::new());
+    }
+
+    #[test]
+    fn two_items_same_queue_time_and_exec_duration() {
+        let tasks = vec![
+            Task {
+                id: 42,
+                queued_at: 1,
+                execution_duration: 3,
+                ..Default::default()
+            },
+            Task {
+                id: 43,
+                queued_at: 1,
+                execution_duration: 3,
+                ..Default::default()
+            },
+        ];
+
+        assert_eq!(execution_order(tasks), vec![42, 43]);
+    }
+
+    #[test]
+    fn task_inserted_into_queue() {
+        // 0...
+        //      queue 42
+        //                  start 42
+        // 1...
+        //      queue 43
+        // 2...
+        //      queue 44
+        // 3...
+        //                              exec 42
+        //                  start 43
+        // 4...
+        //
+        // 5...
+        //      queue 45
+        // 6...
+        //                              exec 43
+        //                  start 44
+        //                              exec 45
+        // 7...
+        //
+        // 8...
+        //                  exec 44
+        let tasks = vec![
+            Task {
+                id: 42,
+                queued_at: 0,
+                execution_duration: 3,
+                ..Default::default()
+            },
+            Task {
+                id: 43,
+                queued_at: 1,
+                execution_duration: 5,
+                ..Default::default()
+            },
+            Task {
+                id: 44,
+                queued_at: 2,
+                execution_duration: 6,
+                ..Default::default()
+            },
+            Task {
+                id: 45,
+                queued_at: 5,
+                execution_duration: 1,
+                ..Default::default()
+            },
+        ];
+
+        assert_eq!(execution_order(tasks), vec![42, 43, 45, 44]);
+    }
+
+    #[test]
+    fn schedule_keeps_timing_and_tasks() {
+        let tasks = vec![
+            Task {
+                id: 42,
+                queued_at: 0,
+                execution_duration: 3,
+                ..Default::default()
+            },
+            Task {
+                id: 43,
+                queued_at: 1,
+                execution_duration: 2,
+                not_before: Some(2),
+                ..Default::default()
+            },
+        ];
+
+        let schedule = execution_schedule(tasks.clone());
+
+        assert_eq!(schedule.order(), execution_order(tasks.clone()));
+        let run = &schedule.runs[1];
+        assert_eq!(run.task, tasks[1]);
+        assert_eq!((run.started_at, run.finished_at), (3, 5));
+        // waiting only counts from `not_before`
+        assert_eq!(run.wait_time, 1);
+    }
+
+    #[test]
+    fn iterator_yields_the_schedule_lazily() {
+        let tasks: Vec<Task> = (0..1000)
+            .map(|id| Task {
+                id,
+                queued_at: id as u32 * 2,
+                execution_duration: 3,
+                ..Default::default()
+            })
+            .collect();
+
+        let first: Vec<ScheduledTask> = execution_order_iter(tasks.clone()).take(3).collect();
+
+        assert_eq!(first, execution_schedule(tasks.clone()).runs[..3]);
+        assert_eq!(
+            execution_order_iter(tasks.clone()).collect::<Vec<_>>(),
+            execution_schedule(tasks).runs
+        );
+        assert_eq!(execution_order_iter(vec![]).next(), None);
+    }
+
+    #[test]
+    fn iterator_honours_dependencies_and_abandonment() {
+        // #1 keeps the CPU until 5, by which time #9 has given up; #7 is reserved for 6 and #4
+        // waits for it
+        let mut tasks = tied_tasks();
+        tasks[1].give_up_after = Some(1);
+        tasks[2].depends_on = vec![7];
+        tasks[3].reserved_at = Some(6);
+
+        let order: Vec<u64> = execution_order_iter(tasks.clone())
+            .map(|run| run.task.id)
+            .collect();
+
+        assert_eq!(order, execution_order(tasks));
+        assert_eq!(order, vec![1, 7, 4]);
+    }
+
+    #[test]
+    fn fallible_api_matches_on_valid_input() {
+        assert_eq!(
+            try_execution_order(tied_tasks()),
+            Ok(execution_schedule(tied_tasks()))
+        );
+    }
+
+    #[test]
+    fn fallible_api_reports_the_offending_task() {
+        let mut tasks = tied_tasks();
+        tasks[2].id = 9;
+        assert_eq!(
+            try_execution_order(tasks).unwrap_err().to_string(),
+            "task 9 appears more than once"
+        );
+
+        // #7 is the first to push the clock past u32::MAX
+        let mut tasks = tied_tasks();
+        tasks[3].execution_duration = u32::MAX - 3;
+        assert_eq!(
+            try_execution_order(tasks),
+            Err(ScheduleError::TimeOverflow { id: 7 })
+        );
+
+        let mut tasks = tied_tasks();
+        tasks[0].depends_on = vec![1];
+        assert_eq!(
+            try_execution_order(tasks).unwrap_err().to_string(),
+            "dependency cycle: #1 -> #1"
+        );
+    }
+
+    fn tied_tasks() -> Vec<Task> {
+        // #1 keeps the CPU busy while the rest queue up with equal durations
+        vec![
+            Task {
+                id: 1,
+                queued_at: 0,
+                execution_duration: 5,
+                ..Default::default()
+            },
+            Task {
+                id: 9,
+                queued_at: 2,
+                execution_duration: 3,
+                ..Default::default()
+            },
+            Task {
+                id: 4,
+                queued_at: 3,
+                execution_duration: 3,
+                ..Default::default()
+            },
+            Task {
+                id: 7,
+                queued_at: 2,
+                execution_duration: 3,
+                ..Default::default()
+            },
+        ]
+    }
+
+    #[test]
+    fn btree_semantics_break_ties_by_id() {
+        assert_eq!(
+            execution_order_with(tied_tasks(), Semantics::BTree),
+            execution_order(tied_tasks())
+        );
+        assert_eq!(
+            execution_order_with(tied_tasks(), Semantics::BTree),
+            vec![1, 4, 7, 9]
+        );
+    }
+
+    #[test]
+    fn original_semantics_break_ties_by_arrival() {
+        assert_eq!(
+            execution_order_with(tied_tasks(), Semantics::Original),
+            execution_order_original(tied_tasks())
+        );
+        assert_eq!(
+            execution_order_with(tied_tasks(), Semantics::Original),
+            vec![1, 9, 7, 4]
+        );
+    }
+
+    #[test]
+    fn lifo_semantics_take_the_latest_arrival() {
+        // #9 and #7 are both queued at 2, but #7 was submitted last
+        assert_eq!(
+            execution_order_with(tied_tasks(), Semantics::Lifo),
+            vec![1, 4, 7, 9]
+        );
+    }
+
+    #[test]
+    fn custom_semantics_use_the_given_comparator() {
+        let highest_id = TieComparator::new(|a, b| b.id.cmp(&a.id));
+
+        assert_eq!(
+            execution_order_with(tied_tasks(), Semantics::Custom(highest_id)),
+            vec![1, 9, 7, 4]
+        );
+    }
+
+    #[test]
+    fn custom_comparator_can_capture_state() {
+        // runs tied tasks in the order of a list known only at run time
+        let preferred = [7, 4, 9];
+        let rank = move |task: &Task| preferred.iter().position(|&id| id == task.id);
+        let follow_list = TieComparator::new(move |a, b| rank(a).cmp(&rank(b)));
+
+        assert_eq!(
+            execution_order_with(tied_tasks(), Semantics::Custom(follow_list)),
+            vec![1, 7, 4, 9]
+        );
+    }
+
+    #[test]
+    fn arrival_order_semantics_follow_submission() {
+        // #9 and #7 are queued later than #4 but were submitted first
+        assert_eq!(
+            execution_order_with(tied_tasks(), Semantics::ArrivalOrder),
+            vec![1, 9, 4, 7]
+        );
+    }
+}
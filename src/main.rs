@@ -1,264 +1,256 @@
-// We need a function that will evaluate the order that a set of tasks will be completed in.
-// When idle, the CPU will take the next task that has been queued with the lowest time to complete.
-
-// queued by moment in time
-// keep CPU busy for exec duration
-// seconds
-// one task at a time
-use std::collections::BTreeMap;
-
-#[derive(Debug)]
-pub struct Task {
-    pub id: u64,
-    pub queued_at: u32,
-    pub execution_duration: u32,
+// Command-line front end: simulates a task list from a JSON file under a chosen policy.
+use clap::{Parser, Subcommand, ValueEnum};
+use fractal_interview::metrics::DeadlineReport;
+use fractal_interview::viz::gantt_ascii;
+use fractal_interview::{
+    EdfScheduler, MultiCoreScheduler, PriorityScheduler, RoundRobinScheduler, Schedule, Scheduler,
+    SjfScheduler, SrtfScheduler, Task,
+};
+use std::error::Error;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process;
+use std::thread;
+use std::time::{Duration, SystemTime};
+
+/// How often `--watch` checks whether the file has changed.
+const WATCH_INTERVAL: Duration = Duration::from_millis(500);
+
+#[derive(Clone, Parser)]
+#[command(
+    name = "fractal-sched",
+    about = "Simulates scheduling policies over a task list"
+)]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
 }
 
-fn remove_first<K: Clone + Ord, V>(map: &mut BTreeMap<K, V>) -> Option<V> {
-    let key = map.keys().cloned().next();
-    key.and_then(|k| map.remove(&k))
+#[derive(Clone, Subcommand)]
+enum Command {
+    /// Runs the tasks in FILE, a JSON array of tasks, and prints when each one ran.
+    Run {
+        file: PathBuf,
+        #[arg(long, value_enum, default_value = "sjf")]
+        policy: Policy,
+        /// Time slice for `--policy rr`.
+        #[arg(long, default_value_t = 1)]
+        quantum: u32,
+        /// Number of CPUs for `--policy multi-core`.
+        #[arg(long, default_value_t = 2)]
+        cpus: usize,
+        /// Also draw the schedule as a text Gantt chart.
+        #[arg(long)]
+        gantt: bool,
+        /// Keep running, and redraw the report whenever FILE changes.
+        #[arg(long)]
+        watch: bool,
+    },
 }
 
-pub fn execution_order(mut tasks: Vec<Task>) -> Vec<u64> {
-    let mut executed = vec![];
-
-    tasks.sort_by_key(|task| task.queued_at);
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum Policy {
+    Sjf,
+    Srtf,
+    Priority,
+    Edf,
+    Rr,
+    MultiCore,
+}
 
-    let mut time = 0_u32;
-    let mut q: BTreeMap<(u32, u64), Task> = BTreeMap::new();
+fn scheduler(policy: Policy, quantum: u32, cpus: usize) -> Box<dyn Scheduler> {
+    match policy {
+        Policy::Sjf => Box::new(SjfScheduler::default()),
+        Policy::Srtf => Box::new(SrtfScheduler::default()),
+        Policy::Priority => Box::new(PriorityScheduler::default()),
+        Policy::Edf => Box::new(EdfScheduler::default()),
+        Policy::Rr => Box::new(RoundRobinScheduler { quantum }),
+        Policy::MultiCore => Box::new(MultiCoreScheduler::new(cpus)),
+    }
+}
 
-    // while there are still tasks to queue & execute
-    while !tasks.is_empty() || !q.is_empty() {
-        // look for any tasks with `queued_at` before/during the current time
-        if !tasks.is_empty() {
-            match tasks.iter().rposition(|task| task.queued_at <= time) {
-                // add any tasks queued before/during the current time to the queue for execution
-                Some(index) => q.extend(
-                    tasks
-                        .drain(..index + 1)
-                        .into_iter()
-                        .map(|task| ((task.execution_duration, task.id), task)),
-                ),
-                // otherwise, no tasks queued before this time range
-                // so update time to match next task b/c computer is currently idle
-                None => time = tasks.first().unwrap().queued_at,
-            }
-        }
-        // execute any items in the queue
-        if let Some(current_task) = remove_first(&mut q) {
-            time += current_task.execution_duration;
-            executed.push(current_task.id);
-        }
+/// One line per task in completion order, under a header.
+fn render(schedule: &Schedule) -> String {
+    let mut out = String::from("id\tstarted\tfinished\twait\n");
+    for run in &schedule.runs {
+        out.push_str(&format!(
+            "{}\t{}\t{}\t{}\n",
+            run.task.id, run.started_at, run.finished_at, run.wait_time
+        ));
     }
 
-    executed
+    if schedule.runs.iter().any(|run| run.task.deadline.is_some()) {
+        let report = DeadlineReport::from(schedule);
+        out.push_str(&format!(
+            "\nhard deadlines missed: {}{}\nsoft deadlines missed: {} (penalty {})\n",
+            report.hard_misses.len(),
+            if report.feasible() {
+                ""
+            } else {
+                " (infeasible)"
+            },
+            report.soft_misses.len(),
+            report.soft_penalty
+        ));
+    }
+    out
 }
 
-pub fn execution_order_original(mut tasks: Vec<Task>) -> Vec<u64> {
-    if tasks.is_empty() {
-        return vec![];
+fn run(cli: Cli) -> Result<String, Box<dyn Error>> {
+    let Command::Run {
+        file,
+        policy,
+        quantum,
+        cpus,
+        gantt,
+        ..
+    } = cli.command;
+    if policy == Policy::Rr && quantum == 0 {
+        return Err("--quantum must be positive".into());
+    }
+    if policy == Policy::MultiCore && cpus == 0 {
+        return Err("--cpus must be positive".into());
     }
 
-    tasks.sort_by_key(|task| task.queued_at);
-
-    let mut time = 0_u32;
-    let mut result: Vec<u64> = vec![];
+    let tasks = Task::from_json_slice(&fs::read(&file)?)?;
+    let scheduler = scheduler(policy, quantum, cpus);
+    scheduler.capabilities().check(&tasks)?;
 
-    loop {
-        let current_task = tasks
-            .iter()
-            .take_while(|task| task.queued_at <= time)
-            .min_by_key(|task| task.execution_duration);
+    let schedule = scheduler.schedule(tasks);
+    let mut out = render(&schedule);
+    if gantt {
+        out.push('\n');
+        out.push_str(&gantt_ascii(&schedule));
+    }
+    Ok(out)
+}
 
-        if let Some(current_task) = current_task {
-            time += current_task.execution_duration;
-            result.push(current_task.id);
+fn modified(file: &Path) -> Option<SystemTime> {
+    fs::metadata(file).and_then(|m| m.modified()).ok()
+}
 
-            if tasks.is_empty() {
-                break;
+/// Reruns `cli` each time `file` changes, until interrupted. Errors are reported without
+/// stopping, since a file caught halfway through being saved may not parse.
+fn watch(cli: Cli, file: &Path) -> ! {
+    let mut last = None;
+    loop {
+        let current = modified(file);
+        if current != last {
+            last = current;
+            // clear the terminal so the report is redrawn in place
+            print!("\x1b[2J\x1b[H");
+            match run(cli.clone()) {
+                Ok(out) => print!("{}", out),
+                Err(err) => eprintln!("fractal-sched: {}", err),
             }
-
-            let index = tasks
-                .iter()
-                .position(|task| task.id == current_task.id)
-                .unwrap();
-            tasks.remove(index);
-        } else if !tasks.is_empty() {
-            time = tasks[0].queued_at;
-        } else {
-            break;
         }
+        thread::sleep(WATCH_INTERVAL);
     }
+}
 
-    result
+fn main() {
+    let cli = Cli::parse();
+    if let Command::Run {
+        file, watch: true, ..
+    } = &cli.command
+    {
+        let file = file.clone();
+        watch(cli, &file);
+    }
+
+    match run(cli) {
+        Ok(out) => print!("{}", out),
+        Err(err) => {
+            eprintln!("fractal-sched: {}", err);
+            process::exit(1);
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
-    #[test]
-    fn reverse_queue_order() {
-        // 44   0   2
-        // 43   2   3
-        // 42   5   3
-
-        let tasks = vec![
-            Task {
-                id: 42,
-                queued_at: 5,
-                execution_duration: 3,
-            },
-            Task {
-                id: 43,
-                queued_at: 2,
-                execution_duration: 3,
-            },
-            Task {
-                id: 44,
-                queued_at: 0,
-                execution_duration: 2,
-            },
-        ];
-
-        assert_eq!(execution_order(tasks), vec![44, 43, 42]);
+    fn workload(name: &str, json: &str) -> PathBuf {
+        let path = std::env::temp_dir().join(format!("fractal-sched-{}.json", name));
+        fs::write(&path, json).unwrap();
+        path
     }
 
-    #[test]
-    fn two_items_queued_at_once() {
-        // 0: #42 is queued
-        // 0: #42 is started
-        // 1: #43 is queued
-        // 2: #44 is queued
-        // 3: #42 is finished
-        // 3: #44 is started (it is queued and has a lower execution_duration than #43)
-        // 5: #44 is finished
-        // 5: #43 is started
-        // 8: #43 is finished
-
-        let tasks = vec![
-            Task {
-                id: 42,
-                queued_at: 0,
-                execution_duration: 3,
-            },
-            Task {
-                id: 43,
-                queued_at: 1,
-                execution_duration: 3,
-            },
-            Task {
-                id: 44,
-                queued_at: 2,
-                execution_duration: 2,
-            },
-        ];
-
-        assert_eq!(execution_order(tasks), vec![42, 44, 43]);
+    fn cli(args: &[&str]) -> Cli {
+        Cli::try_parse_from([&["fractal-sched", "run"], args].concat()).unwrap()
     }
 
     #[test]
-    fn idle() {
-        // 0: #42 is queued
-        // 0: #42 is started
-        // 1: #43 is queued
-        // 2: #44 is queued
-        // 3: #42 is finished
-        // 3: #44 is started (it is queued and has a lower execution_duration than #43)
-        // 5: #44 is finished
-        // 5: #43 is started
-        // 8: #43 is finished
-
-        let tasks = vec![
-            Task {
-                id: 42,
-                queued_at: 0,
-                execution_duration: 1,
-            },
-            Task {
-                id: 43,
-                queued_at: 3,
-                execution_duration: 3,
-            },
-        ];
-
-        assert_eq!(execution_order(tasks), vec![42, 43]);
+    fn runs_the_chosen_policy() {
+        let path = workload(
+            "policy",
+            r#"[
+                {"id": 1, "execution_duration": 4},
+                {"id": 2, "queued_at": 1, "execution_duration": 1}
+            ]"#,
+        );
+        let file = path.to_str().unwrap();
+
+        assert_eq!(
+            run(cli(&[file])).unwrap(),
+            "id\tstarted\tfinished\twait\n1\t0\t4\t0\n2\t4\t5\t3\n"
+        );
+        assert_eq!(
+            run(cli(&[file, "--policy", "srtf"])).unwrap(),
+            "id\tstarted\tfinished\twait\n2\t1\t2\t0\n1\t0\t5\t0\n"
+        );
     }
 
     #[test]
-    fn empty_task_list() {
-        assert_eq!(execution_order(vec![]), vec![]);
+    fn reports_hard_and_soft_misses_apart() {
+        let path = workload(
+            "deadlines",
+            r#"[
+                {"id": 1, "execution_duration": 4, "deadline": 3, "deadline_kind": "hard"},
+                {"id": 2, "execution_duration": 4, "deadline": 6}
+            ]"#,
+        );
+
+        assert_eq!(
+            run(cli(&[path.to_str().unwrap(), "--policy", "edf"])).unwrap(),
+            "id\tstarted\tfinished\twait\n1\t0\t4\t0\n2\t4\t8\t4\n\n\
+             hard deadlines missed: 1 (infeasible)\nsoft deadlines missed: 1 (penalty 2)\n"
+        );
     }
 
     #[test]
-    fn two_items_same_queue_time_and_exec_duration() {
-        let tasks = vec![
-            Task {
-                id: 42,
-                queued_at: 1,
-                execution_duration: 3,
-            },
-            Task {
-                id: 43,
-                queued_at: 1,
-                execution_duration: 3,
-            },
-        ];
+    fn rejects_fields_the_policy_ignores() {
+        let path = workload("reserved", r#"[{"id": 7, "reserved_at": 3}]"#);
+
+        let err = run(cli(&[path.to_str().unwrap(), "--policy", "rr"])).unwrap_err();
 
-        assert_eq!(execution_order(tasks), vec![42, 43]);
+        assert_eq!(
+            err.to_string(),
+            "task 7 sets `reserved_at`, which this policy does not support"
+        );
     }
 
     #[test]
-    fn task_inserted_into_queue() {
-        // 0...
-        //      queue 42
-        //                  start 42
-        // 1...
-        //      queue 43
-        // 2...
-        //      queue 44
-        // 3...
-        //                              exec 42
-        //                  start 43
-        // 4...
-        //
-        // 5...
-        //      queue 45
-        // 6...
-        //                              exec 43
-        //                  start 44
-        //                              exec 45
-        // 7...
-        //
-        // 8...
-        //                  exec 44
-        let tasks = vec![
-            Task {
-                id: 42,
-                queued_at: 0,
-                execution_duration: 3,
-            },
-            Task {
-                id: 43,
-                queued_at: 1,
-                execution_duration: 5,
-            },
-            Task {
-                id: 44,
-                queued_at: 2,
-                execution_duration: 6,
-            },
-            Task {
-                id: 45,
-                queued_at: 5,
-                execution_duration: 1,
-            },
-        ];
-
-        assert_eq!(execution_order(tasks), vec![42, 43, 45, 44]);
+    fn watched_file_changes_are_noticed() {
+        let path = workload("watch", "[]");
+        let before = modified(&path);
+        assert!(before.is_some());
+
+        thread::sleep(Duration::from_millis(20));
+        fs::write(&path, r#"[{"id": 1}]"#).unwrap();
+
+        assert_ne!(modified(&path), before);
+        assert_eq!(
+            run(cli(&[path.to_str().unwrap(), "--watch"])).unwrap(),
+            "id\tstarted\tfinished\twait\n1\t0\t0\t0\n"
+        );
     }
-}
 
-fn main() {
-    println!("Hello, world!");
+    #[test]
+    fn unknown_policy_is_a_usage_error() {
+        assert!(
+            Cli::try_parse_from(["fractal-sched", "run", "x.json", "--policy", "lifo"]).is_err()
+        );
+    }
 }
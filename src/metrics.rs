@@ -0,0 +1,534 @@
+// Headline numbers computed straight from a `Schedule`, whichever policy produced it.
+use crate::stats::ScheduleStats;
+use crate::workload::WorkloadModel;
+use crate::{DeadlineKind, Schedule, Scheduler, Task};
+
+/// Timing and utilization of a finished schedule: the timing of `ScheduleStats`, plus how busy
+/// the CPUs were. Rates are over the time from when the first task was queued to when the last
+/// one finished.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct ScheduleMetrics {
+    /// Mean time from when a task became ready to when it first started.
+    pub mean_wait: f64,
+    pub max_wait: u32,
+    /// Mean time from when a task was queued to when it finished.
+    pub mean_turnaround: f64,
+    /// When the last task finished.
+    pub makespan: u32,
+    /// Fraction of the time the CPUs were busy. `from` only counts cores that ran something, as
+    /// the schedule doesn't record idle ones; `with_cores` counts all of them.
+    pub utilization: f64,
+    /// Tasks completed per second.
+    pub throughput: f64,
+}
+
+impl ScheduleMetrics {
+    /// The metrics of `schedule` as run on `cores` CPUs, busy or not. Panics if some task ran
+    /// on a core past `cores`.
+    pub fn with_cores(schedule: &Schedule, cores: usize) -> Self {
+        let busy = schedule.timelines().len();
+        assert!(
+            busy <= cores,
+            "schedule uses {} cores, more than the {} given",
+            busy,
+            cores
+        );
+        ScheduleMetrics::measure(schedule, cores.max(1))
+    }
+
+    fn measure(schedule: &Schedule, cores: usize) -> Self {
+        let runs = &schedule.runs;
+        if runs.is_empty() {
+            return ScheduleMetrics::default();
+        }
+        let stats = ScheduleStats::from(schedule);
+        let count = runs.len() as f64;
+
+        let first_queued = runs.iter().map(|run| run.task.queued_at).min().unwrap();
+        let span = f64::from(stats.makespan - first_queued);
+        let busy: u64 = schedule
+            .segments
+            .iter()
+            .map(|segment| u64::from(segment.end - segment.start))
+            .sum();
+        let cores = cores as f64;
+
+        ScheduleMetrics {
+            mean_wait: stats.mean_wait,
+            max_wait: stats.max_wait,
+            mean_turnaround: stats.mean_turnaround,
+            makespan: stats.makespan,
+            utilization: if span == 0.0 {
+                0.0
+            } else {
+                busy as f64 / (cores * span)
+            },
+            throughput: if span == 0.0 { 0.0 } else { count / span },
+        }
+    }
+}
+
+impl From<&Schedule> for ScheduleMetrics {
+    fn from(schedule: &Schedule) -> Self {
+        ScheduleMetrics::measure(schedule, schedule.timelines().len().max(1))
+    }
+}
+
+/// How much waiting one task caused the others in a schedule.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DelayImpact {
+    pub id: u64,
+    /// Total time other tasks spent ready but not running while this one held a CPU.
+    pub imposed: u64,
+    /// How many other tasks were kept waiting by it at some point.
+    pub tasks_delayed: usize,
+}
+
+/// For each task in `schedule`, how much it delayed the others, most disruptive first (ties by
+/// id). A task counts as waiting from when it became ready until it finished, whenever it isn't
+/// running, so time spent preempted counts too. On several cores, time a task spends waiting is
+/// charged to every task running at that moment, as any of them finishing would have freed a
+/// core for it.
+pub fn delay_impact(schedule: &Schedule) -> Vec<DelayImpact> {
+    let waiting: Vec<(u64, Vec<(u32, u32)>)> = schedule
+        .runs
+        .iter()
+        .map(|run| {
+            let id = run.task.id;
+            let mut running: Vec<(u32, u32)> = schedule
+                .segments
+                .iter()
+                .filter(|segment| segment.id == id)
+                .map(|segment| (segment.start, segment.end))
+                .collect();
+            running.sort_unstable();
+            let mut gaps = vec![];
+            let mut from = run.task.ready_at();
+            for (start, end) in running {
+                if start > from {
+                    gaps.push((from, start));
+                }
+                from = from.max(end);
+            }
+            if run.finished_at > from {
+                gaps.push((from, run.finished_at));
+            }
+            (id, gaps)
+        })
+        .collect();
+
+    let mut impacts: Vec<DelayImpact> = schedule
+        .runs
+        .iter()
+        .map(|run| {
+            let id = run.task.id;
+            let mut impact = DelayImpact {
+                id,
+                imposed: 0,
+                tasks_delayed: 0,
+            };
+            for (other, gaps) in &waiting {
+                if *other == id {
+                    continue;
+                }
+                let overlap: u64 = schedule
+                    .segments
+                    .iter()
+                    .filter(|segment| segment.id == id)
+                    .flat_map(|segment| {
+                        gaps.iter().map(move |&(from, to)| {
+                            u64::from(to.min(segment.end).saturating_sub(from.max(segment.start)))
+                        })
+                    })
+                    .sum();
+                if overlap > 0 {
+                    impact.imposed += overlap;
+                    impact.tasks_delayed += 1;
+                }
+            }
+            impact
+        })
+        .collect();
+    impacts.sort_by_key(|impact| (std::cmp::Reverse(impact.imposed), impact.id));
+    impacts
+}
+
+/// Missed deadlines in a schedule, hard and soft kept apart: any hard miss makes the schedule
+/// infeasible, while soft misses only add to a penalty.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct DeadlineReport {
+    /// Ids of tasks that missed a hard deadline, in completion order.
+    pub hard_misses: Vec<u64>,
+    /// Ids of tasks that missed a soft deadline, in completion order.
+    pub soft_misses: Vec<u64>,
+    /// Total lateness of the soft misses.
+    pub soft_penalty: u64,
+}
+
+impl DeadlineReport {
+    /// Whether every hard deadline was met.
+    pub fn feasible(&self) -> bool {
+        self.hard_misses.is_empty()
+    }
+}
+
+impl From<&Schedule> for DeadlineReport {
+    fn from(schedule: &Schedule) -> Self {
+        let mut report = DeadlineReport::default();
+        for run in schedule.runs.iter().filter(|run| run.missed_deadline()) {
+            match run.task.deadline_kind {
+                DeadlineKind::Hard => report.hard_misses.push(run.task.id),
+                DeadlineKind::Soft => {
+                    report.soft_misses.push(run.task.id);
+                    report.soft_penalty += u64::from(run.lateness());
+                }
+            }
+        }
+        report
+    }
+}
+
+/// How much `candidate` changed the metrics of one replication relative to `baseline`
+/// (`candidate - baseline`), both run on the same workload.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PairedDelta {
+    /// Index of the replication's workload (or seed) in the input.
+    pub replication: usize,
+    pub mean_wait: f64,
+    pub mean_turnaround: f64,
+    pub makespan: f64,
+}
+
+/// A mean over replications, with its standard error.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Estimate {
+    pub mean: f64,
+    pub std_error: f64,
+}
+
+/// Result of `compare_paired`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PairedComparison {
+    pub deltas: Vec<PairedDelta>,
+}
+
+impl PairedComparison {
+    pub fn mean_wait(&self) -> Option<Estimate> {
+        self.estimate(|delta| delta.mean_wait)
+    }
+
+    pub fn mean_turnaround(&self) -> Option<Estimate> {
+        self.estimate(|delta| delta.mean_turnaround)
+    }
+
+    pub fn makespan(&self) -> Option<Estimate> {
+        self.estimate(|delta| delta.makespan)
+    }
+
+    /// `None` without any replications; the standard error is 0 with just one.
+    fn estimate<F: Fn(&PairedDelta) -> f64>(&self, f: F) -> Option<Estimate> {
+        let n = self.deltas.len() as f64;
+        if self.deltas.is_empty() {
+            return None;
+        }
+        let mean = self.deltas.iter().map(&f).sum::<f64>() / n;
+        let variance = if self.deltas.len() > 1 {
+            self.deltas
+                .iter()
+                .map(|d| (f(d) - mean).powi(2))
+                .sum::<f64>()
+                / (n - 1.0)
+        } else {
+            0.0
+        };
+        Some(Estimate {
+            mean,
+            std_error: (variance / n).sqrt(),
+        })
+    }
+}
+
+/// Compares two policies with common random numbers: for each seed, one workload of `count`
+/// tasks is generated from `model` and run under both policies, so the deltas reflect the
+/// policies rather than differences between sampled workloads.
+pub fn compare_paired(
+    model: &WorkloadModel,
+    count: usize,
+    seeds: &[u64],
+    baseline: &dyn Scheduler,
+    candidate: &dyn Scheduler,
+) -> PairedComparison {
+    let workloads: Vec<Vec<Task>> = seeds
+        .iter()
+        .map(|&seed| model.generate(count, seed))
+        .collect();
+    compare_replications(&workloads, baseline, candidate)
+}
+
+/// Like `compare_paired`, over workloads already generated, e.g. by
+/// `WorkloadModel::replications` with a variance-reducing `Sampling`.
+pub fn compare_replications(
+    workloads: &[Vec<Task>],
+    baseline: &dyn Scheduler,
+    candidate: &dyn Scheduler,
+) -> PairedComparison {
+    let deltas = workloads
+        .iter()
+        .enumerate()
+        .map(|(replication, workload)| {
+            let base = ScheduleMetrics::from(&baseline.schedule(workload.clone()));
+            let cand = ScheduleMetrics::from(&candidate.schedule(workload.clone()));
+            PairedDelta {
+                replication,
+                mean_wait: cand.mean_wait - base.mean_wait,
+                mean_turnaround: cand.mean_turnaround - base.mean_turnaround,
+                makespan: f64::from(cand.makespan) - f64::from(base.makespan),
+            }
+        })
+        .collect();
+    PairedComparison { deltas }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::workload::DurationSummary;
+    use crate::workload::Sampling;
+    use crate::{execution_schedule, MultiCoreScheduler, RoundRobinScheduler, SjfScheduler};
+
+    fn tasks() -> Vec<Task> {
+        vec![
+            Task {
+                id: 42,
+                queued_at: 0,
+                execution_duration: 3,
+                ..Default::default()
+            },
+            Task {
+                id: 43,
+                queued_at: 1,
+                execution_duration: 2,
+                ..Default::default()
+            },
+            Task {
+                id: 44,
+                queued_at: 8,
+                execution_duration: 2,
+                ..Default::default()
+            },
+        ]
+    }
+
+    #[test]
+    fn delay_impact_charges_waits_to_the_running_task() {
+        // 0: #42 is started (3)
+        // 1: #43 is queued (2), waits 2 behind #42
+        // 2: #45 is queued (4), waits 1 behind #42 and 2 behind #43
+        // 5: #45 is started
+        // 9: #44 is queued, #45 is finished
+        let mut tasks = tasks();
+        tasks.push(Task {
+            id: 45,
+            queued_at: 2,
+            execution_duration: 4,
+            ..Default::default()
+        });
+        tasks[2].queued_at = 9;
+
+        let impacts = delay_impact(&execution_schedule(tasks));
+
+        assert_eq!(
+            impacts,
+            vec![
+                DelayImpact {
+                    id: 42,
+                    imposed: 3,
+                    tasks_delayed: 2,
+                },
+                DelayImpact {
+                    id: 43,
+                    imposed: 2,
+                    tasks_delayed: 1,
+                },
+                DelayImpact {
+                    id: 44,
+                    imposed: 0,
+                    tasks_delayed: 0,
+                },
+                DelayImpact {
+                    id: 45,
+                    imposed: 0,
+                    tasks_delayed: 0,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn delay_impact_counts_time_spent_preempted() {
+        // 0-1: #42, 1-2: #43, 2-3: #42, 3-4: #43, 4-5: #42
+        // #42 is kept off the CPU twice by #43, #43 once by #42
+        let tasks = vec![
+            Task {
+                id: 42,
+                queued_at: 0,
+                execution_duration: 3,
+                ..Default::default()
+            },
+            Task {
+                id: 43,
+                queued_at: 1,
+                execution_duration: 2,
+                ..Default::default()
+            },
+        ];
+
+        let impacts = delay_impact(&RoundRobinScheduler { quantum: 1 }.schedule(tasks));
+
+        assert_eq!(
+            impacts
+                .iter()
+                .map(|i| (i.id, i.imposed))
+                .collect::<Vec<_>>(),
+            vec![(43, 2), (42, 1)]
+        );
+    }
+
+    #[test]
+    fn metrics_of_a_single_cpu_schedule() {
+        // 0: #42 is started
+        // 3: #42 is finished, #43 is started after waiting 2
+        // 5: #43 is finished, CPU idles
+        // 8: #44 is started
+        // 10: #44 is finished
+        let metrics = ScheduleMetrics::from(&execution_schedule(tasks()));
+
+        assert_eq!(
+            metrics,
+            ScheduleMetrics {
+                mean_wait: 2.0 / 3.0,
+                max_wait: 2,
+                mean_turnaround: (3.0 + 4.0 + 2.0) / 3.0,
+                makespan: 10,
+                utilization: 0.7,
+                throughput: 0.3,
+            }
+        );
+    }
+
+    #[test]
+    fn utilization_is_shared_across_cores() {
+        let metrics = ScheduleMetrics::from(&MultiCoreScheduler::new(2).schedule(tasks()));
+
+        assert_eq!(metrics.max_wait, 0);
+        assert_eq!(metrics.utilization, 7.0 / 20.0);
+    }
+
+    #[test]
+    fn idle_cores_count_towards_utilization() {
+        // #42 and #43 share the first two cores; the other six never run anything
+        let schedule = MultiCoreScheduler::new(8).schedule(tasks());
+
+        assert_eq!(schedule.timelines().len(), 2);
+        assert_eq!(
+            ScheduleMetrics::with_cores(&schedule, 8).utilization,
+            7.0 / 80.0
+        );
+        assert_eq!(ScheduleMetrics::from(&schedule).utilization, 7.0 / 20.0);
+    }
+
+    #[test]
+    #[should_panic(expected = "more than the 1 given")]
+    fn with_cores_rejects_too_few_cores() {
+        ScheduleMetrics::with_cores(&MultiCoreScheduler::new(2).schedule(tasks()), 1);
+    }
+
+    #[test]
+    fn deadline_misses_are_split_by_kind() {
+        // #42 finishes at 3, #43 at 5 and #44 at 10
+        let mut tasks = tasks();
+        tasks[0].deadline = Some(2);
+        tasks[0].deadline_kind = DeadlineKind::Hard;
+        tasks[1].deadline = Some(4);
+        tasks[2].deadline = Some(7);
+
+        let report = DeadlineReport::from(&execution_schedule(tasks.clone()));
+
+        assert_eq!(
+            report,
+            DeadlineReport {
+                hard_misses: vec![42],
+                soft_misses: vec![43, 44],
+                soft_penalty: 1 + 3,
+            }
+        );
+        assert!(!report.feasible());
+
+        tasks[0].deadline = Some(3);
+        assert!(DeadlineReport::from(&execution_schedule(tasks)).feasible());
+    }
+
+    #[test]
+    fn empty_schedule() {
+        assert_eq!(
+            ScheduleMetrics::from(&Schedule::default()),
+            ScheduleMetrics::default()
+        );
+    }
+
+    fn model() -> WorkloadModel {
+        WorkloadModel {
+            arrival_rate: 0.2,
+            durations: DurationSummary {
+                min: 1,
+                mean: 4.0,
+                p50: 3,
+                p90: 8,
+                p99: 15,
+                max: 20,
+            },
+        }
+    }
+
+    #[test]
+    fn paired_comparison_of_a_policy_with_itself_is_exact() {
+        let sjf = SjfScheduler::default();
+
+        let comparison = compare_paired(&model(), 200, &[1, 2, 3], &sjf, &sjf);
+
+        assert_eq!(
+            comparison.mean_wait(),
+            Some(Estimate {
+                mean: 0.0,
+                std_error: 0.0,
+            })
+        );
+        assert_eq!(comparison.deltas.len(), 3);
+    }
+
+    #[test]
+    fn same_realization_goes_to_both_policies() {
+        let sjf = SjfScheduler::default();
+        let rr = RoundRobinScheduler { quantum: 1 };
+
+        let comparison = compare_paired(&model(), 200, &[1, 2, 3, 4], &sjf, &rr);
+
+        // total work and arrivals are shared, so neither policy can finish it sooner
+        assert_eq!(comparison.makespan().unwrap().mean, 0.0);
+        assert!(comparison.mean_turnaround().unwrap().mean > 0.0);
+        assert_eq!(PairedComparison { deltas: vec![] }.mean_wait(), None);
+    }
+
+    #[test]
+    fn compare_pre_generated_replications() {
+        let sjf = SjfScheduler::default();
+        let rr = RoundRobinScheduler { quantum: 1 };
+        let workloads = model().replications(200, 8, 5, Sampling::Antithetic);
+
+        let comparison = compare_replications(&workloads, &sjf, &rr);
+
+        assert_eq!(comparison.deltas.len(), 8);
+        assert_eq!(comparison.deltas[7].replication, 7);
+        assert!(comparison.mean_turnaround().unwrap().mean > 0.0);
+    }
+}
@@ -0,0 +1,229 @@
+// Incremental scheduling: tasks are fed in as they arrive rather than all up front.
+use crate::scheduler::Tie;
+use crate::{ScheduledTask, Semantics, Task};
+use std::collections::{BTreeMap, VecDeque};
+
+/// Non-preemptive shortest job first on a single CPU, like `SjfScheduler`, driven step by step
+/// to model a live queue whose arrivals aren't known in advance. Push tasks as they become
+/// known, advance the clock, and collect runs as they finish.
+///
+/// A task can only be pushed if it becomes ready no earlier than the current time. Every
+/// decision the CPU made before then is final. With that rule, pushing a workload in input order
+/// and advancing past its end gives the same runs as `SjfScheduler` with the same `semantics`.
+///
+/// Reservations, abandonment and dependencies are not supported.
+#[derive(Debug, Clone, Default)]
+pub struct SimScheduler {
+    semantics: Semantics,
+    now: u32,
+    cpu_free: u32,
+    submitted: u64,
+    /// Tasks not yet ready, keyed by (ready time, sequence number).
+    pending: BTreeMap<(u32, u64), Task>,
+    /// Ready tasks, keyed by (duration, tie-break, sequence number) so the first runs next.
+    ready: BTreeMap<(u32, Tie, u64), Task>,
+    running: Option<(Task, u32)>,
+    completed: VecDeque<ScheduledTask>,
+}
+
+impl SimScheduler {
+    pub fn new(semantics: Semantics) -> Self {
+        SimScheduler {
+            semantics,
+            ..Default::default()
+        }
+    }
+
+    /// The time the scheduler has been advanced to.
+    pub fn now(&self) -> u32 {
+        self.now
+    }
+
+    /// Submits `task`. Panics if it would have been ready before the current time.
+    pub fn push(&mut self, task: Task) {
+        assert!(
+            task.ready_at() >= self.now,
+            "task {} is ready at {}, before the current time {}",
+            task.id,
+            task.ready_at(),
+            self.now
+        );
+        self.pending.insert((task.ready_at(), self.submitted), task);
+        self.submitted += 1;
+    }
+
+    /// Runs the CPU up to `time`. Tasks finishing at or before `time` become available from
+    /// `next_completed`. Nothing is dispatched at `time` itself, since tasks arriving at that
+    /// instant may still be pushed. Panics if `time` is in the past.
+    pub fn advance_to(&mut self, time: u32) {
+        assert!(
+            time >= self.now,
+            "cannot go back from {} to {}",
+            self.now,
+            time
+        );
+
+        loop {
+            if let Some((task, started_at)) = self.running.take() {
+                let finished_at = started_at + task.execution_duration;
+                if finished_at > time {
+                    self.running = Some((task, started_at));
+                    break;
+                }
+                self.cpu_free = finished_at;
+                self.completed
+                    .push_back(ScheduledTask::new(task, started_at, finished_at));
+            }
+
+            // idle until the next arrival if nothing is waiting
+            let mut dispatch_at = self.cpu_free;
+            if self.ready.is_empty() {
+                match self.pending.keys().next() {
+                    Some(&(ready_at, _)) => dispatch_at = dispatch_at.max(ready_at),
+                    None => break,
+                }
+            }
+            if dispatch_at >= time {
+                break;
+            }
+
+            while let Some(entry) = self.pending.first_entry() {
+                if entry.key().0 > dispatch_at {
+                    break;
+                }
+                let ((_, seq), task) = entry.remove_entry();
+                let key = (
+                    task.execution_duration,
+                    self.semantics.tie_break(&task, seq),
+                    seq,
+                );
+                self.ready.insert(key, task);
+            }
+            let (_, task) = self.ready.pop_first().unwrap();
+            self.running = Some((task, dispatch_at));
+        }
+
+        self.now = time;
+    }
+
+    /// The earliest finished run not yet returned, in completion order.
+    pub fn next_completed(&mut self) -> Option<ScheduledTask> {
+        self.completed.pop_front()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Scheduler, SjfScheduler};
+
+    fn tasks() -> Vec<Task> {
+        [
+            (1, 0, 5),
+            (9, 2, 3),
+            (4, 3, 3),
+            (7, 2, 3),
+            (2, 20, 1),
+            (3, 21, 2),
+        ]
+        .iter()
+        .map(|&(id, queued_at, execution_duration)| Task {
+            id,
+            queued_at,
+            execution_duration,
+            ..Default::default()
+        })
+        .collect()
+    }
+
+    fn drain(scheduler: &mut SimScheduler) -> Vec<ScheduledTask> {
+        std::iter::from_fn(|| scheduler.next_completed()).collect()
+    }
+
+    #[test]
+    fn pushing_as_tasks_arrive_matches_batch() {
+        for semantics in [
+            Semantics::BTree,
+            Semantics::Original,
+            Semantics::ArrivalOrder,
+        ] {
+            let mut scheduler = SimScheduler::new(semantics.clone());
+            let mut runs = vec![];
+            let mut arrivals = tasks();
+            arrivals.sort_by_key(|task| task.queued_at);
+            for task in arrivals {
+                scheduler.advance_to(task.queued_at);
+                runs.extend(drain(&mut scheduler));
+                scheduler.push(task);
+            }
+            scheduler.advance_to(u32::MAX);
+            runs.extend(drain(&mut scheduler));
+
+            let mut sorted = tasks();
+            sorted.sort_by_key(|task| task.queued_at);
+            assert_eq!(runs, SjfScheduler { semantics }.schedule(sorted).runs);
+        }
+    }
+
+    #[test]
+    fn reports_only_finished_runs() {
+        let mut scheduler = SimScheduler::default();
+        for task in tasks() {
+            scheduler.push(task);
+        }
+
+        // #1 runs 0-5, then #4 5-8
+        scheduler.advance_to(7);
+        let runs = drain(&mut scheduler);
+        assert_eq!(runs.len(), 1);
+        assert_eq!((runs[0].task.id, runs[0].finished_at), (1, 5));
+
+        scheduler.advance_to(8);
+        assert_eq!(scheduler.next_completed().unwrap().task.id, 4);
+        assert_eq!(scheduler.next_completed(), None);
+    }
+
+    #[test]
+    fn arrival_at_current_time_is_still_considered() {
+        let mut scheduler = SimScheduler::default();
+        scheduler.push(Task {
+            id: 1,
+            queued_at: 0,
+            execution_duration: 2,
+            ..Default::default()
+        });
+        scheduler.advance_to(2);
+        // #1 finished at 2, but the CPU hasn't picked its next task yet
+        scheduler.push(Task {
+            id: 2,
+            queued_at: 2,
+            execution_duration: 5,
+            ..Default::default()
+        });
+        scheduler.push(Task {
+            id: 3,
+            queued_at: 2,
+            execution_duration: 1,
+            ..Default::default()
+        });
+        scheduler.advance_to(10);
+
+        let order: Vec<u64> = drain(&mut scheduler)
+            .iter()
+            .map(|run| run.task.id)
+            .collect();
+        assert_eq!(order, vec![1, 3, 2]);
+    }
+
+    #[test]
+    #[should_panic(expected = "before the current time")]
+    fn pushing_into_the_past_panics() {
+        let mut scheduler = SimScheduler::default();
+        scheduler.advance_to(5);
+        scheduler.push(Task {
+            id: 1,
+            queued_at: 4,
+            ..Default::default()
+        });
+    }
+}
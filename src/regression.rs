@@ -0,0 +1,210 @@
+// Comparing a run against a stored baseline, so scheduler config changes can be gated on
+// simulation results.
+use crate::stats::ScheduleStats;
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+/// A statistic that can regress. Every metric is a cost, so higher values are worse.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Metric {
+    Makespan,
+    MeanWait,
+    MaxWait,
+    P99Wait,
+    MeanTurnaround,
+    MissedStartBy,
+}
+
+impl Metric {
+    pub const ALL: [Metric; 6] = [
+        Metric::Makespan,
+        Metric::MeanWait,
+        Metric::MaxWait,
+        Metric::P99Wait,
+        Metric::MeanTurnaround,
+        Metric::MissedStartBy,
+    ];
+
+    /// The name used for the metric in baseline files.
+    pub fn name(self) -> &'static str {
+        match self {
+            Metric::Makespan => "makespan",
+            Metric::MeanWait => "mean_wait",
+            Metric::MaxWait => "max_wait",
+            Metric::P99Wait => "p99_wait",
+            Metric::MeanTurnaround => "mean_turnaround",
+            Metric::MissedStartBy => "missed_start_by",
+        }
+    }
+
+    pub fn of(self, stats: &ScheduleStats) -> f64 {
+        match self {
+            Metric::Makespan => f64::from(stats.makespan),
+            Metric::MeanWait => stats.mean_wait,
+            Metric::MaxWait => f64::from(stats.max_wait),
+            Metric::P99Wait => f64::from(stats.p99_wait),
+            Metric::MeanTurnaround => stats.mean_turnaround,
+            Metric::MissedStartBy => stats.missed_start_by as f64,
+        }
+    }
+}
+
+/// A metric that got worse than its tolerance allows.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Regression {
+    pub metric: Metric,
+    pub baseline: f64,
+    pub current: f64,
+    /// Allowed relative increase over the baseline, e.g. `0.05` for 5%.
+    pub tolerance: f64,
+}
+
+/// Compares `current` against `baseline`, returning every metric in `tolerances` that exceeds
+/// the baseline by more than its allowed relative increase (so `(Metric::P99Wait, 0.05)` means
+/// "p99 wait within 5%"). A baseline of zero tolerates no increase at all.
+pub fn find_regressions(
+    baseline: &ScheduleStats,
+    current: &ScheduleStats,
+    tolerances: &[(Metric, f64)],
+) -> Vec<Regression> {
+    tolerances
+        .iter()
+        .filter_map(|&(metric, tolerance)| {
+            let (baseline, current) = (metric.of(baseline), metric.of(current));
+            if current > baseline * (1.0 + tolerance) {
+                Some(Regression {
+                    metric,
+                    baseline,
+                    current,
+                    tolerance,
+                })
+            } else {
+                None
+            }
+        })
+        .collect()
+}
+
+/// Writes `stats` to `path` as `name = value` lines, one per metric.
+pub fn save_baseline<P: AsRef<Path>>(path: P, stats: &ScheduleStats) -> io::Result<()> {
+    let contents: String = Metric::ALL
+        .iter()
+        .map(|metric| format!("{} = {}\n", metric.name(), metric.of(stats)))
+        .collect();
+    fs::write(path, contents)
+}
+
+/// Reads a baseline previously written by `save_baseline`.
+pub fn load_baseline<P: AsRef<Path>>(path: P) -> io::Result<ScheduleStats> {
+    let invalid = |message: String| io::Error::new(io::ErrorKind::InvalidData, message);
+
+    let mut values = BTreeMap::new();
+    for (number, line) in fs::read_to_string(path)?.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        let (name, value) = line
+            .split_once('=')
+            .ok_or_else(|| invalid(format!("line {}: expected `name = value`", number + 1)))?;
+        let metric = Metric::ALL
+            .iter()
+            .find(|metric| metric.name() == name.trim())
+            .ok_or_else(|| invalid(format!("line {}: unknown metric `{}`", number + 1, name)))?;
+        let value: f64 = value
+            .trim()
+            .parse()
+            .map_err(|_| invalid(format!("line {}: `{}` is not a number", number + 1, value)))?;
+        values.insert(*metric, value);
+    }
+
+    let get = |metric: Metric| {
+        values
+            .get(&metric)
+            .copied()
+            .ok_or_else(|| invalid(format!("missing metric `{}`", metric.name())))
+    };
+
+    Ok(ScheduleStats {
+        makespan: get(Metric::Makespan)? as u32,
+        mean_wait: get(Metric::MeanWait)?,
+        max_wait: get(Metric::MaxWait)? as u32,
+        p99_wait: get(Metric::P99Wait)? as u32,
+        mean_turnaround: get(Metric::MeanTurnaround)?,
+        missed_start_by: get(Metric::MissedStartBy)? as usize,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stats() -> ScheduleStats {
+        ScheduleStats {
+            makespan: 100,
+            mean_wait: 12.5,
+            max_wait: 40,
+            p99_wait: 38,
+            mean_turnaround: 20.25,
+            missed_start_by: 0,
+        }
+    }
+
+    #[test]
+    fn regressions_respect_tolerances() {
+        let current = ScheduleStats {
+            p99_wait: 40,
+            mean_wait: 13.0,
+            missed_start_by: 1,
+            ..stats()
+        };
+        let tolerances = [
+            (Metric::P99Wait, 0.05),
+            (Metric::MeanWait, 0.05),
+            (Metric::MissedStartBy, 0.0),
+        ];
+
+        let regressions = find_regressions(&stats(), &current, &tolerances);
+
+        // 38 -> 40 is more than 5%, 12.5 -> 13 is within it, and any missed start is a regression
+        let metrics: Vec<Metric> = regressions.iter().map(|r| r.metric).collect();
+        assert_eq!(metrics, vec![Metric::P99Wait, Metric::MissedStartBy]);
+        assert_eq!(regressions[0].baseline, 38.0);
+        assert_eq!(regressions[0].current, 40.0);
+    }
+
+    #[test]
+    fn improvements_are_not_regressions() {
+        let current = ScheduleStats {
+            makespan: 50,
+            ..stats()
+        };
+
+        assert!(find_regressions(&stats(), &current, &[(Metric::Makespan, 0.0)]).is_empty());
+    }
+
+    #[test]
+    fn baseline_round_trips_through_file() {
+        let path = std::env::temp_dir().join(format!("baseline-{}.txt", std::process::id()));
+
+        save_baseline(&path, &stats()).unwrap();
+        let loaded = load_baseline(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(loaded, stats());
+    }
+
+    #[test]
+    fn malformed_baseline_is_rejected() {
+        let path = std::env::temp_dir().join(format!("bad-baseline-{}.txt", std::process::id()));
+
+        fs::write(&path, "makespan = soon\n").unwrap();
+        let error = load_baseline(&path).unwrap_err();
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+        assert!(error.to_string().contains("line 1"));
+    }
+}
@@ -0,0 +1,90 @@
+// A small deterministic PRNG (SplitMix64) so seeded simulations are reproducible without pulling
+// in a dependency.
+
+/// 64-bit FNV-1a: a fixed, platform-independent hash of `bytes`.
+pub(crate) fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xCBF2_9CE4_8422_2325_u64, |hash, &byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01B3)
+    })
+}
+
+#[derive(Debug, Clone)]
+pub struct SplitMix64 {
+    state: u64,
+}
+
+impl SplitMix64 {
+    pub fn new(seed: u64) -> Self {
+        SplitMix64 { state: seed }
+    }
+
+    /// An independent stream for the component called `name`, so each source of randomness in a
+    /// simulation can be reseeded without perturbing the others.
+    pub fn stream(seed: u64, name: &str) -> Self {
+        // hashed, so the same name always picks the same stream
+        SplitMix64::new(SplitMix64::new(seed ^ fnv1a(name.as_bytes())).next_u64())
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// A uniformly distributed value in `[0, 1)`.
+    pub fn next_f64(&mut self) -> f64 {
+        // the top 53 bits fill an f64 mantissa exactly
+        (self.next_u64() >> 11) as f64 / (1_u64 << 53) as f64
+    }
+
+    /// A uniformly distributed value in `0..n`. Panics if `n` is zero.
+    pub fn below(&mut self, n: u64) -> u64 {
+        assert!(n > 0, "cannot draw from an empty range");
+        // widening multiply avoids the bias of `next_u64() % n`
+        ((u128::from(self.next_u64()) * u128::from(n)) >> 64) as u64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn same_seed_same_sequence() {
+        let mut a = SplitMix64::new(7);
+        let mut b = SplitMix64::new(7);
+        for _ in 0..100 {
+            assert_eq!(a.next_u64(), b.next_u64());
+        }
+    }
+
+    #[test]
+    fn below_stays_in_range() {
+        let mut rng = SplitMix64::new(1);
+        for _ in 0..1000 {
+            assert!(rng.below(3) < 3);
+        }
+    }
+
+    #[test]
+    fn named_streams_differ() {
+        let mut a = SplitMix64::stream(7, "arrivals");
+        let mut b = SplitMix64::stream(7, "durations");
+        let mut again = SplitMix64::stream(7, "arrivals");
+
+        let first = a.next_u64();
+        assert_ne!(first, b.next_u64());
+        assert_eq!(first, again.next_u64());
+    }
+
+    #[test]
+    fn next_f64_stays_in_unit_interval() {
+        let mut rng = SplitMix64::new(1);
+        for _ in 0..1000 {
+            let x = rng.next_f64();
+            assert!((0.0..1.0).contains(&x));
+        }
+    }
+}
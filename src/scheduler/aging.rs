@@ -0,0 +1,139 @@
+// Aging: SJF where waiting makes a task look shorter, so long tasks can't starve.
+use super::{Job, PolicyCapabilities, Schedule, ScheduledTask, Scheduler, Semantics};
+use crate::Task;
+
+/// Non-preemptive shortest job first on a single CPU with aging. Whenever the CPU is idle, it
+/// takes the ready task with the lowest effective duration: its `execution_duration` minus
+/// `rate` for every second it has waited. However many short tasks keep arriving, a long one
+/// eventually looks shortest of all. Ties go to the shorter task, then according to
+/// `semantics`. With a `rate` of 0 this is plain `SjfScheduler`.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct AgingScheduler {
+    pub rate: f64,
+    pub semantics: Semantics,
+}
+
+impl Scheduler for AgingScheduler {
+    fn schedule(&self, tasks: Vec<Task>) -> Schedule {
+        assert!(self.rate >= 0.0, "aging rate must not be negative");
+
+        let mut pending = Job::pending(tasks);
+        let mut ready: Vec<Job> = vec![];
+        let mut runs = vec![];
+        let mut time = 0_u32;
+
+        loop {
+            while pending
+                .last()
+                .is_some_and(|job| job.task.ready_at() <= time)
+            {
+                ready.push(pending.pop().unwrap());
+            }
+
+            let effective = |job: &Job| {
+                let waited = f64::from(time - job.task.ready_at());
+                f64::from(job.task.execution_duration) - self.rate * waited
+            };
+            let next = ready
+                .iter()
+                .enumerate()
+                .min_by(|(_, a), (_, b)| {
+                    effective(a).total_cmp(&effective(b)).then_with(|| {
+                        let key = |job: &Job| {
+                            (
+                                job.task.execution_duration,
+                                self.semantics.tie_break(&job.task, job.seq),
+                                job.seq,
+                            )
+                        };
+                        key(a).cmp(&key(b))
+                    })
+                })
+                .map(|(i, _)| i);
+
+            match next {
+                Some(i) => {
+                    let task = ready.swap_remove(i).task;
+                    let started_at = time;
+                    time += task.execution_duration;
+                    runs.push(ScheduledTask::new(task, started_at, time));
+                }
+                // idle until the next arrival, if there is one
+                None => match pending.last() {
+                    Some(job) => time = job.task.ready_at(),
+                    None => break,
+                },
+            }
+        }
+
+        Schedule::from_runs(runs)
+    }
+
+    fn capabilities(&self) -> PolicyCapabilities {
+        PolicyCapabilities {
+            deterministic: true,
+            ..Default::default()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::SjfScheduler;
+
+    /// A long task queued alongside a stream of short ones, one arriving every second.
+    fn starving() -> Vec<Task> {
+        let mut tasks = vec![Task {
+            id: 0,
+            queued_at: 0,
+            execution_duration: 10,
+            ..Default::default()
+        }];
+        tasks.extend((1..=30).map(|id| Task {
+            id,
+            queued_at: id as u32 - 1,
+            execution_duration: 1,
+            ..Default::default()
+        }));
+        tasks
+    }
+
+    #[test]
+    fn long_task_starves_without_aging() {
+        let schedule = SjfScheduler::default().schedule(starving());
+
+        assert_eq!(schedule.order().last(), Some(&0));
+    }
+
+    #[test]
+    fn aging_lets_the_long_task_in() {
+        // at 0.5 a second, #0 ties with the fresh arrival at 18 (shorter wins) and wins at 19
+        let schedule = AgingScheduler {
+            rate: 0.5,
+            ..Default::default()
+        }
+        .schedule(starving());
+
+        let run = schedule.runs.iter().find(|run| run.task.id == 0).unwrap();
+        assert_eq!(run.started_at, 19);
+    }
+
+    #[test]
+    fn zero_rate_matches_sjf() {
+        for semantics in [
+            Semantics::BTree,
+            Semantics::Original,
+            Semantics::ArrivalOrder,
+        ] {
+            assert_eq!(
+                AgingScheduler {
+                    rate: 0.0,
+                    semantics: semantics.clone(),
+                }
+                .schedule(starving()),
+                SjfScheduler { semantics }.schedule(starving())
+            );
+        }
+    }
+}
@@ -0,0 +1,182 @@
+// Batch accelerators: tasks run together in exclusive batches, like ML inference on a GPU.
+use super::{Job, PolicyCapabilities, Schedule, ScheduledTask, Scheduler, Segment};
+use crate::Task;
+use std::collections::VecDeque;
+
+/// An accelerator that runs tasks only in batches of up to `max_batch`. A batch has the device
+/// to itself: it pays `setup` once, runs its tasks side by side, and every task in it finishes
+/// when the longest does. Tasks join batches in the order they become ready (ties by input
+/// order).
+///
+/// When the device is free it starts a batch as soon as `max_batch` tasks are ready, or once
+/// the oldest ready task has waited `max_wait`, whichever comes first. A `max_wait` of 0 starts
+/// with whatever is ready; a longer one trades latency for fuller batches.
+///
+/// Each task's segment covers its whole batch, with `core` its lane within the batch.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BatchScheduler {
+    pub max_batch: usize,
+    pub setup: u32,
+    pub max_wait: u32,
+}
+
+impl BatchScheduler {
+    pub fn new(max_batch: usize, setup: u32) -> Self {
+        BatchScheduler {
+            max_batch,
+            setup,
+            max_wait: 0,
+        }
+    }
+}
+
+impl Scheduler for BatchScheduler {
+    fn schedule(&self, tasks: Vec<Task>) -> Schedule {
+        assert!(self.max_batch > 0, "batches must hold at least one task");
+
+        let mut pending = Job::pending(tasks);
+        let mut ready: VecDeque<Task> = VecDeque::new();
+        let mut schedule = Schedule::default();
+        let mut time = 0_u32;
+
+        loop {
+            while pending
+                .last()
+                .is_some_and(|job| job.task.ready_at() <= time)
+            {
+                ready.push_back(pending.pop().unwrap().task);
+            }
+            let next_arrival = pending.last().map(|job| job.task.ready_at());
+
+            let oldest = match ready.front() {
+                Some(task) => task.ready_at(),
+                // idle until the next arrival, if there is one
+                None => match next_arrival {
+                    Some(at) => {
+                        time = at;
+                        continue;
+                    }
+                    None => break,
+                },
+            };
+            let start_by = oldest.saturating_add(self.max_wait).max(time);
+            if ready.len() < self.max_batch && time < start_by {
+                // wait for the batch to fill, but no longer than the oldest task will
+                time = next_arrival.map_or(start_by, |at| at.min(start_by));
+                continue;
+            }
+
+            let batch: Vec<Task> = ready.drain(..ready.len().min(self.max_batch)).collect();
+            let longest = batch.iter().map(|task| task.execution_duration).max();
+            let end = time + self.setup + longest.unwrap();
+            for (lane, task) in batch.into_iter().enumerate() {
+                schedule.segments.push(Segment {
+                    id: task.id,
+                    core: lane,
+                    start: time,
+                    end,
+                });
+                schedule.runs.push(ScheduledTask::new(task, time, end));
+            }
+            time = end;
+        }
+
+        schedule
+    }
+
+    fn capabilities(&self) -> PolicyCapabilities {
+        PolicyCapabilities {
+            deterministic: true,
+            ..Default::default()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn requests() -> Vec<Task> {
+        // 1   0   3
+        // 2   1   1
+        // 3   2   2
+        // 4   3   1
+        [(1, 0, 3), (2, 1, 1), (3, 2, 2), (4, 3, 1)]
+            .iter()
+            .map(|&(id, queued_at, execution_duration)| Task {
+                id,
+                queued_at,
+                execution_duration,
+                ..Default::default()
+            })
+            .collect()
+    }
+
+    fn batches(schedule: &Schedule) -> Vec<(u64, usize, u32, u32)> {
+        schedule
+            .segments
+            .iter()
+            .map(|s| (s.id, s.core, s.start, s.end))
+            .collect()
+    }
+
+    #[test]
+    fn batches_start_with_whatever_is_ready() {
+        // 0: #1 is started alone, with 2 of setup
+        // 5: #2, #3 and #4 are started together, finishing with #3
+        let schedule = BatchScheduler::new(4, 2).schedule(requests());
+
+        assert_eq!(
+            batches(&schedule),
+            vec![(1, 0, 0, 5), (2, 0, 5, 9), (3, 1, 5, 9), (4, 2, 5, 9)]
+        );
+        assert_eq!(schedule.runs[3].wait_time, 2);
+    }
+
+    #[test]
+    fn waiting_fills_the_batch() {
+        // 3: #4 makes four, so the batch starts before #1 has waited its full 5
+        let schedule = BatchScheduler {
+            max_wait: 5,
+            ..BatchScheduler::new(4, 2)
+        }
+        .schedule(requests());
+
+        assert_eq!(
+            batches(&schedule),
+            vec![(1, 0, 3, 8), (2, 1, 3, 8), (3, 2, 3, 8), (4, 3, 3, 8)]
+        );
+    }
+
+    #[test]
+    fn oldest_task_waits_at_most_max_wait() {
+        // 2: #1 has waited 2, so #1 to #3 go without #4
+        let schedule = BatchScheduler {
+            max_wait: 2,
+            ..BatchScheduler::new(4, 2)
+        }
+        .schedule(requests());
+
+        assert_eq!(schedule.order(), vec![1, 2, 3, 4]);
+        assert_eq!(schedule.runs[0].started_at, 2);
+        assert_eq!(
+            (schedule.runs[3].started_at, schedule.runs[3].finished_at),
+            (7, 10)
+        );
+    }
+
+    #[test]
+    fn batches_are_capped() {
+        // 0: #1 alone; 5: #2 and #3 fill a batch of two; 9: #4
+        let schedule = BatchScheduler::new(2, 2).schedule(requests());
+
+        let starts: Vec<u32> = schedule.runs.iter().map(|run| run.started_at).collect();
+        assert_eq!(starts, vec![0, 5, 5, 9]);
+    }
+
+    #[test]
+    #[should_panic(expected = "batches must hold at least one task")]
+    fn empty_batches() {
+        BatchScheduler::new(0, 1).schedule(requests());
+    }
+}
@@ -0,0 +1,120 @@
+// Cost of delay: each task costs something for every second it waits to be done.
+use super::{dispatch_by, PolicyCapabilities, Schedule, Scheduler, Semantics};
+use crate::Task;
+use std::cmp::{Ordering, Reverse};
+
+/// Non-preemptive cμ rule on a single CPU: whenever the CPU is idle, it takes the ready task with
+/// the highest `delay_cost` per second of `execution_duration`, which greedily keeps the total
+/// cost of delay (`Schedule::total_delay_cost`) down. Zero-length tasks hold nobody up and go
+/// first; ties go to the shorter task, then according to `semantics`.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct CostOfDelayScheduler {
+    pub semantics: Semantics,
+}
+
+/// `delay_cost / execution_duration`, compared exactly. The duration must be positive.
+#[derive(Debug, Clone, Copy)]
+struct CostRate {
+    cost: u64,
+    duration: u64,
+}
+
+impl PartialEq for CostRate {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for CostRate {}
+
+impl PartialOrd for CostRate {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for CostRate {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (self.cost * other.duration).cmp(&(other.cost * self.duration))
+    }
+}
+
+impl Scheduler for CostOfDelayScheduler {
+    fn schedule(&self, tasks: Vec<Task>) -> Schedule {
+        dispatch_by(tasks, |task, seq| {
+            let rate = CostRate {
+                cost: u64::from(task.delay_cost),
+                duration: u64::from(task.execution_duration.max(1)),
+            };
+            (
+                task.execution_duration > 0,
+                Reverse(rate),
+                task.execution_duration,
+                self.semantics.tie_break(task, seq),
+                seq,
+            )
+        })
+    }
+
+    fn capabilities(&self) -> PolicyCapabilities {
+        PolicyCapabilities {
+            deterministic: true,
+            ..Default::default()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::SjfScheduler;
+
+    fn tasks() -> Vec<Task> {
+        // #1 keeps the CPU busy while the rest queue up
+        [(1, 0, 2, 1), (2, 1, 4, 8), (3, 1, 1, 1), (4, 1, 3, 9)]
+            .iter()
+            .map(|&(id, queued_at, execution_duration, delay_cost)| Task {
+                id,
+                queued_at,
+                execution_duration,
+                delay_cost,
+                ..Default::default()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn highest_cost_per_second_runs_first() {
+        // after #1: #4 costs 3 a second of its length, #2 costs 2, #3 costs 1
+        let schedule = CostOfDelayScheduler::default().schedule(tasks());
+
+        assert_eq!(schedule.order(), vec![1, 4, 2, 3]);
+    }
+
+    #[test]
+    fn costs_less_than_sjf() {
+        // cμ: #1 0-2, #4 2-5, #2 5-9, #3 9-10
+        // SJF: #1 0-2, #3 2-3, #4 3-6, #2 6-10
+        let cost = CostOfDelayScheduler::default().schedule(tasks());
+        let sjf = SjfScheduler::default().schedule(tasks());
+
+        assert_eq!(cost.total_delay_cost(), 2 + 9 * 4 + 8 * 8 + 9);
+        assert_eq!(sjf.total_delay_cost(), 2 + 2 + 9 * 5 + 8 * 9);
+    }
+
+    #[test]
+    fn without_costs_shortest_runs_first() {
+        let tasks: Vec<Task> = tasks()
+            .into_iter()
+            .map(|task| Task {
+                delay_cost: 0,
+                ..task
+            })
+            .collect();
+
+        assert_eq!(
+            CostOfDelayScheduler::default().schedule(tasks.clone()),
+            SjfScheduler::default().schedule(tasks)
+        );
+    }
+}
@@ -0,0 +1,285 @@
+// Credit scheduling, after Xen's: tasks earn CPU credit over time and spend it while running.
+use super::{Job, PolicyCapabilities, Schedule, ScheduleEvent, ScheduledTask, Scheduler, Segment};
+use crate::Task;
+use std::collections::VecDeque;
+
+/// Preemptive credit scheduling on a single CPU. Every `period`, each task in the system earns
+/// `credits` (never holding more than `credits` at once), and a running task spends one credit
+/// per second it runs. The scheduler always runs a task from the best `CreditState` available,
+/// round robin with time slices of `quantum` within a state, and a task in a better state
+/// preempts one in a worse state as soon as it appears.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CreditScheduler {
+    pub quantum: u32,
+    pub period: u32,
+    pub credits: u32,
+}
+
+/// Where a task stands with the credit scheduler, best first.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum CreditState {
+    /// Newly arrived and yet to use up a time slice, so latency-sensitive work gets in quickly.
+    Boost,
+    /// Has credit left.
+    Under,
+    /// Has spent all its credit, so only runs when nothing is `Boost` or `Under`.
+    Over,
+}
+
+struct Account {
+    job: Job,
+    credits: i64,
+    boosted: bool,
+}
+
+impl Account {
+    fn state(&self) -> CreditState {
+        if self.boosted {
+            CreditState::Boost
+        } else if self.credits > 0 {
+            CreditState::Under
+        } else {
+            CreditState::Over
+        }
+    }
+}
+
+/// The first queued account in the best state.
+fn best(queue: &VecDeque<Account>) -> Option<(usize, CreditState)> {
+    queue
+        .iter()
+        .enumerate()
+        .min_by_key(|(_, account)| account.state())
+        .map(|(i, account)| (i, account.state()))
+}
+
+impl Scheduler for CreditScheduler {
+    fn schedule(&self, tasks: Vec<Task>) -> Schedule {
+        assert!(
+            self.quantum > 0 && self.period > 0,
+            "quantum and period must be positive"
+        );
+
+        let mut pending = Job::pending(tasks);
+        let mut schedule = Schedule::default();
+        let mut time = 0_u32;
+        let mut next_period = 0_u32;
+        let mut queue: VecDeque<Account> = VecDeque::new();
+        // the running account, when its segment started and how much of its slice it has used
+        let mut running: Option<(Account, u32, u32)> = None;
+        let cap = i64::from(self.credits);
+
+        loop {
+            while pending
+                .last()
+                .is_some_and(|job| job.task.ready_at() <= time)
+            {
+                queue.push_back(Account {
+                    job: pending.pop().unwrap(),
+                    credits: 0,
+                    boosted: true,
+                });
+            }
+            while next_period <= time {
+                let active = queue.iter_mut().chain(running.as_mut().map(|(a, _, _)| a));
+                for account in active {
+                    account.credits = (account.credits + cap).min(cap);
+                }
+                next_period += self.period;
+            }
+
+            if let Some((mut account, start, used)) = running.take() {
+                let expired = used >= self.quantum;
+                if expired {
+                    account.boosted = false;
+                }
+                let current = account.state();
+                match best(&queue) {
+                    // a spent slice goes to the next task in the same state; a better state
+                    // doesn't wait for the slice to end
+                    Some((i, state)) if state < current || (expired && state == current) => {
+                        account.boosted = false;
+                        schedule.segments.push(Segment {
+                            id: account.job.task.id,
+                            core: 0,
+                            start,
+                            end: time,
+                        });
+                        schedule.events.push(ScheduleEvent::Preempted {
+                            time,
+                            id: account.job.task.id,
+                            by: queue[i].job.task.id,
+                        });
+                        queue.push_back(account);
+                    }
+                    _ => running = Some((account, start, if expired { 0 } else { used })),
+                }
+            }
+
+            if running.is_none() {
+                match best(&queue) {
+                    Some((i, _)) => {
+                        let mut account = queue.remove(i).unwrap();
+                        match account.job.first_started {
+                            Some(_) => schedule.events.push(ScheduleEvent::Resumed {
+                                time,
+                                id: account.job.task.id,
+                            }),
+                            None => account.job.first_started = Some(time),
+                        }
+                        running = Some((account, time, 0));
+                    }
+                    // idle until the next arrival, if there is one
+                    None => match pending.last() {
+                        Some(job) => {
+                            time = job.task.ready_at();
+                            continue;
+                        }
+                        None => break,
+                    },
+                }
+            }
+
+            // run until the slice ends, the task finishes, credit is handed out or a task
+            // arrives, whichever comes first, then reconsider
+            let (mut account, start, used) = running.take().unwrap();
+            let step = [
+                self.quantum - used,
+                account.job.remaining,
+                next_period - time,
+            ]
+            .iter()
+            .copied()
+            .chain(pending.last().map(|job| job.task.ready_at() - time))
+            .min()
+            .unwrap();
+            time += step;
+            account.job.remaining -= step;
+            account.credits -= i64::from(step);
+
+            if account.job.remaining == 0 {
+                let id = account.job.task.id;
+                schedule.segments.push(Segment {
+                    id,
+                    core: 0,
+                    start,
+                    end: time,
+                });
+                let started_at = account.job.first_started.unwrap();
+                schedule
+                    .runs
+                    .push(ScheduledTask::new(account.job.task, started_at, time));
+            } else {
+                running = Some((account, start, used + step));
+            }
+        }
+
+        schedule
+    }
+
+    fn capabilities(&self) -> PolicyCapabilities {
+        PolicyCapabilities {
+            preemptive: true,
+            deterministic: true,
+            ..Default::default()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn segments(schedule: &Schedule) -> Vec<(u64, u32, u32)> {
+        schedule
+            .segments
+            .iter()
+            .map(|s| (s.id, s.start, s.end))
+            .collect()
+    }
+
+    fn task(id: u64, queued_at: u32, execution_duration: u32) -> Task {
+        Task {
+            id,
+            queued_at,
+            execution_duration,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn boosted_arrival_gets_the_next_slice() {
+        // 0: #1 is queued and boosted, earns 4 credits and is started
+        // 1: #2 is queued and boosted, but doesn't outrank #1
+        // 2: #1's slice is up, so it loses its boost and #2 runs
+        // 4: #2 is finished, #1 earns credit back and is resumed
+        // 8: #1 is finished
+        let policy = CreditScheduler {
+            quantum: 2,
+            period: 4,
+            credits: 4,
+        };
+
+        let schedule = policy.schedule(vec![task(1, 0, 6), task(2, 1, 2)]);
+
+        assert_eq!(segments(&schedule), vec![(1, 0, 2), (2, 2, 4), (1, 4, 8)]);
+        assert_eq!(schedule.order(), vec![2, 1]);
+    }
+
+    #[test]
+    fn every_new_task_gets_a_boosted_slice_first() {
+        let policy = CreditScheduler {
+            quantum: 4,
+            period: 8,
+            credits: 4,
+        };
+
+        let schedule = policy.schedule(vec![task(1, 0, 10), task(2, 0, 10), task(3, 0, 10)]);
+
+        assert_eq!(
+            segments(&schedule)[..4],
+            [(1, 0, 4), (2, 4, 8), (3, 8, 12), (1, 12, 16)]
+        );
+    }
+
+    #[test]
+    fn boost_preempts_an_over_task() {
+        let policy = CreditScheduler {
+            quantum: 4,
+            period: 100,
+            credits: 1,
+        };
+
+        let schedule = policy.schedule(vec![task(1, 0, 10), task(2, 6, 1)]);
+
+        assert_eq!(segments(&schedule), vec![(1, 0, 6), (2, 6, 7), (1, 7, 11)]);
+        assert_eq!(
+            schedule.events[0],
+            ScheduleEvent::Preempted {
+                time: 6,
+                id: 1,
+                by: 2,
+            }
+        );
+    }
+
+    #[test]
+    fn tasks_out_of_credit_wait_for_those_with_some() {
+        // 0: #1 is started alone and runs on past its credit
+        // 10: #2 is queued and boosted, preempting #1
+        // 12: #2's slice is up, but it has credit left and #1 doesn't, so it carries on
+        // 14: #2 is out of credit, so the two take turns
+        let policy = CreditScheduler {
+            quantum: 2,
+            period: 10,
+            credits: 3,
+        };
+
+        let schedule = policy.schedule(vec![task(1, 0, 20), task(2, 10, 6)]);
+
+        assert_eq!(
+            segments(&schedule)[..4],
+            [(1, 0, 10), (2, 10, 14), (1, 14, 16), (2, 16, 18)]
+        );
+    }
+}
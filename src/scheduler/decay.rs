@@ -0,0 +1,208 @@
+// Priority decay: the longer a task runs, the further it sinks within its priority class.
+use super::{Job, PolicyCapabilities, Schedule, ScheduleEvent, ScheduledTask, Scheduler, Segment};
+use crate::Task;
+use std::cmp::Reverse;
+use std::collections::BTreeMap;
+
+/// Preemptive priority scheduling on a single CPU where CPU hogs yield to newcomers. Tasks are
+/// ordered by `priority` as under `PriorityScheduler`, then by their decay: `decay(time run so
+/// far)`, lowest first, so a task that has run for a while falls behind fresh arrivals of the
+/// same priority but never behind a lower priority. Remaining ties go to the task submitted
+/// first.
+///
+/// The running task is re-evaluated whenever a task arrives and after every `quantum` of
+/// running. It is preempted if a queued task now ranks strictly ahead of it.
+#[derive(Debug, Clone, Copy)]
+pub struct DecayScheduler {
+    pub quantum: u32,
+    /// The decay curve. Should not decrease as the run time grows.
+    pub decay: fn(u32) -> u32,
+}
+
+impl Default for DecayScheduler {
+    /// Linear decay, re-evaluated every unit of time.
+    fn default() -> Self {
+        DecayScheduler {
+            quantum: 1,
+            decay: |ran| ran,
+        }
+    }
+}
+
+impl DecayScheduler {
+    fn key(&self, job: &Job) -> (Reverse<Option<u8>>, u32, u64) {
+        let ran = job.task.execution_duration - job.remaining;
+        (Reverse(job.task.priority), (self.decay)(ran), job.seq)
+    }
+}
+
+impl Scheduler for DecayScheduler {
+    fn schedule(&self, tasks: Vec<Task>) -> Schedule {
+        assert!(self.quantum > 0, "quantum must be positive");
+
+        let mut pending = Job::pending(tasks);
+        let mut schedule = Schedule::default();
+        let mut time = 0_u32;
+        let mut ready = BTreeMap::new();
+        // the running job, and when its current segment started
+        let mut running: Option<(Job, u32)> = None;
+
+        loop {
+            while pending
+                .last()
+                .is_some_and(|job| job.task.ready_at() <= time)
+            {
+                let job = pending.pop().unwrap();
+                ready.insert(self.key(&job), job);
+            }
+
+            if let Some((job, start)) = running.take() {
+                let key = self.key(&job);
+                match ready.first_key_value() {
+                    Some((first, next)) if *first < key => {
+                        schedule.segments.push(Segment {
+                            id: job.task.id,
+                            core: 0,
+                            start,
+                            end: time,
+                        });
+                        schedule.events.push(ScheduleEvent::Preempted {
+                            time,
+                            id: job.task.id,
+                            by: next.task.id,
+                        });
+                        ready.insert(key, job);
+                    }
+                    _ => running = Some((job, start)),
+                }
+            }
+
+            if running.is_none() {
+                match ready.pop_first() {
+                    Some((_, mut job)) => {
+                        match job.first_started {
+                            Some(_) => schedule.events.push(ScheduleEvent::Resumed {
+                                time,
+                                id: job.task.id,
+                            }),
+                            None => job.first_started = Some(time),
+                        }
+                        running = Some((job, time));
+                    }
+                    // idle until the next arrival, if there is one
+                    None => match pending.last() {
+                        Some(job) => {
+                            time = job.task.ready_at();
+                            continue;
+                        }
+                        None => break,
+                    },
+                }
+            }
+
+            // run for a quantum, or until the job finishes or the next arrival if sooner
+            let (mut job, start) = running.take().unwrap();
+            let mut slice = self.quantum.min(job.remaining);
+            if let Some(next) = pending.last() {
+                slice = slice.min(next.task.ready_at() - time);
+            }
+            time += slice;
+            job.remaining -= slice;
+            if job.remaining > 0 {
+                running = Some((job, start));
+                continue;
+            }
+            schedule.segments.push(Segment {
+                id: job.task.id,
+                core: 0,
+                start,
+                end: time,
+            });
+            let started_at = job.first_started.unwrap();
+            schedule
+                .runs
+                .push(ScheduledTask::new(job.task, started_at, time));
+        }
+
+        schedule
+    }
+
+    fn capabilities(&self) -> PolicyCapabilities {
+        PolicyCapabilities {
+            preemptive: true,
+            priorities: true,
+            deterministic: true,
+            ..Default::default()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn task(id: u64, queued_at: u32, execution_duration: u32, priority: Option<u8>) -> Task {
+        Task {
+            id,
+            queued_at,
+            execution_duration,
+            priority,
+            ..Default::default()
+        }
+    }
+
+    fn spans(schedule: &Schedule) -> Vec<(u64, u32, u32)> {
+        schedule
+            .segments
+            .iter()
+            .map(|s| (s.id, s.start, s.end))
+            .collect()
+    }
+
+    #[test]
+    fn hog_yields_to_newcomer_in_its_class() {
+        // 0: #1 is started
+        // 3: #2 is queued fresh while #1 has decayed to 3, so #1 is preempted
+        // 5: #2 is finished, #1 is resumed
+        let tasks = vec![task(1, 0, 10, Some(5)), task(2, 3, 2, Some(5))];
+
+        let schedule = DecayScheduler::default().schedule(tasks);
+
+        assert_eq!(spans(&schedule), vec![(1, 0, 3), (2, 3, 5), (1, 5, 12)]);
+        assert_eq!(schedule.order(), vec![2, 1]);
+    }
+
+    #[test]
+    fn decay_never_drops_below_a_lower_class() {
+        let tasks = vec![task(1, 0, 10, Some(5)), task(2, 3, 2, Some(4))];
+
+        let schedule = DecayScheduler::default().schedule(tasks);
+
+        assert_eq!(spans(&schedule), vec![(1, 0, 10), (2, 10, 12)]);
+    }
+
+    #[test]
+    fn decay_curve_is_configurable() {
+        // #1 only decays once it has run for 5, so #2 waits until then
+        let tasks = vec![task(1, 0, 10, None), task(2, 3, 2, None)];
+        let scheduler = DecayScheduler {
+            decay: |ran| ran / 5,
+            ..Default::default()
+        };
+
+        let schedule = scheduler.schedule(tasks);
+
+        assert_eq!(spans(&schedule), vec![(1, 0, 5), (2, 5, 7), (1, 7, 12)]);
+        assert_eq!(
+            schedule.events,
+            vec![
+                ScheduleEvent::Preempted {
+                    time: 5,
+                    id: 1,
+                    by: 2,
+                },
+                ScheduleEvent::Resumed { time: 7, id: 1 },
+            ]
+        );
+    }
+}
@@ -0,0 +1,94 @@
+// Earliest deadline first: the ready task due soonest runs next.
+use super::{dispatch_by, PolicyCapabilities, Schedule, Scheduler, Semantics};
+use crate::Task;
+
+/// Non-preemptive earliest deadline first on a single CPU: whenever the CPU is idle, it takes
+/// the ready task with the earliest `deadline`, then the lowest `execution_duration`, then
+/// breaks ties according to `semantics`. Tasks without a deadline run after every task with
+/// one. See `Schedule::missed_deadlines` for which tasks finished late.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct EdfScheduler {
+    pub semantics: Semantics,
+}
+
+impl Scheduler for EdfScheduler {
+    fn schedule(&self, tasks: Vec<Task>) -> Schedule {
+        dispatch_by(tasks, |task, seq| {
+            (
+                task.deadline.is_none(),
+                task.deadline,
+                task.execution_duration,
+                self.semantics.tie_break(task, seq),
+                seq,
+            )
+        })
+    }
+
+    fn capabilities(&self) -> PolicyCapabilities {
+        PolicyCapabilities {
+            deadlines: true,
+            deterministic: true,
+            ..Default::default()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::SjfScheduler;
+
+    fn tasks() -> Vec<Task> {
+        vec![
+            Task {
+                id: 42,
+                queued_at: 0,
+                execution_duration: 1,
+                ..Default::default()
+            },
+            Task {
+                id: 43,
+                queued_at: 0,
+                execution_duration: 4,
+                deadline: Some(5),
+                ..Default::default()
+            },
+            Task {
+                id: 44,
+                queued_at: 0,
+                execution_duration: 2,
+                deadline: Some(3),
+                ..Default::default()
+            },
+        ]
+    }
+
+    #[test]
+    fn earliest_deadline_runs_first() {
+        // 0: #44 is started, due at 3
+        // 2: #44 is finished, #43 is started, due at 5
+        // 6: #43 is finished a second late, #42 is started
+        let schedule = EdfScheduler::default().schedule(tasks());
+
+        assert_eq!(schedule.order(), vec![44, 43, 42]);
+        assert_eq!(schedule.missed_deadlines(), vec![43]);
+        assert!(!schedule.runs[0].missed_deadline());
+    }
+
+    #[test]
+    fn deadlines_are_flagged_under_any_policy() {
+        // SJF runs #42 and #44 first, leaving #43 to finish at 7
+        let schedule = SjfScheduler::default().schedule(tasks());
+
+        assert_eq!(schedule.order(), vec![42, 44, 43]);
+        assert_eq!(schedule.missed_deadlines(), vec![43]);
+        assert!(SjfScheduler::default()
+            .capabilities()
+            .check(&tasks())
+            .is_err());
+        assert_eq!(
+            EdfScheduler::default().capabilities().check(&tasks()),
+            Ok(())
+        );
+    }
+}
@@ -0,0 +1,270 @@
+// Weighted fair sharing in the style of Linux CFS: whoever has had the least weighted CPU runs.
+use super::switching::stitch;
+use super::{Job, PolicyCapabilities, Schedule, Scheduler, Segment};
+use crate::Task;
+use std::collections::BTreeMap;
+
+/// The weight of each `nice` level from -20 to 19, as in CFS: each level is worth about 10% of
+/// the CPU relative to its neighbour.
+const NICE_WEIGHTS: [u64; 40] = [
+    88761, 71755, 56483, 46273, 36291, 29154, 23254, 18705, 14949, 11916, 9548, 7620, 6100, 4904,
+    3906, 3121, 2501, 1991, 1586, 1277, 1024, 820, 655, 526, 423, 335, 272, 215, 172, 137, 110, 87,
+    70, 56, 45, 36, 29, 23, 18, 15,
+];
+
+/// Virtual runtime accrued by a nice 0 task per second on the CPU.
+const NICE_0_VRUNTIME: u64 = 1024;
+
+fn weight(task: &Task) -> u64 {
+    assert!(
+        (-20..=19).contains(&task.nice),
+        "task {} has nice {}, outside -20 to 19",
+        task.id,
+        task.nice
+    );
+    NICE_WEIGHTS[(i32::from(task.nice) + 20) as usize]
+}
+
+/// A simplified Linux CFS on a single CPU. Every task accrues virtual runtime while it runs,
+/// faster the higher its `nice`, and the ready task with the least runs next, for at most
+/// `slice` or until the next arrival, whichever is sooner. Ties go to the earlier arrival (then
+/// input order). So equally nice tasks share the CPU evenly, and a task's share of a contended
+/// CPU is proportional to its weight.
+///
+/// A newly ready task starts at the least virtual runtime of those already queued rather than
+/// at zero, so it gets its fair share from then on instead of the CPU to itself until it has
+/// caught up. Task ids must be unique.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FairScheduler {
+    pub slice: u32,
+}
+
+impl Default for FairScheduler {
+    fn default() -> Self {
+        FairScheduler { slice: 1 }
+    }
+}
+
+impl Scheduler for FairScheduler {
+    fn schedule(&self, tasks: Vec<Task>) -> Schedule {
+        assert!(self.slice > 0, "slice must be positive");
+
+        let mut originals = BTreeMap::new();
+        for task in &tasks {
+            assert!(
+                originals.insert(task.id, task.clone()).is_none(),
+                "task {} appears more than once",
+                task.id
+            );
+        }
+
+        let mut pending = Job::pending(tasks);
+        // (virtual runtime, job), in no particular order
+        let mut ready: Vec<(u64, Job)> = vec![];
+        let mut min_vruntime = 0_u64;
+        let mut pieces = vec![];
+        let mut time = 0_u32;
+
+        loop {
+            while pending
+                .last()
+                .is_some_and(|job| job.task.ready_at() <= time)
+            {
+                ready.push((min_vruntime, pending.pop().unwrap()));
+            }
+            let next_arrival = pending.last().map(|job| job.task.ready_at());
+
+            let next = ready
+                .iter()
+                .enumerate()
+                .min_by_key(|(_, (vruntime, job))| (*vruntime, job.task.ready_at(), job.seq))
+                .map(|(i, _)| i);
+            let i = match next {
+                Some(i) => i,
+                // idle until the next arrival, if there is one
+                None => match next_arrival {
+                    Some(at) => {
+                        time = at;
+                        continue;
+                    }
+                    None => break,
+                },
+            };
+
+            let (vruntime, job) = &mut ready[i];
+            let mut slice = self.slice.min(job.remaining);
+            if let Some(at) = next_arrival {
+                slice = slice.min(at - time);
+            }
+            pieces.push(Segment {
+                id: job.task.id,
+                core: 0,
+                start: time,
+                end: time + slice,
+            });
+            time += slice;
+            job.remaining -= slice;
+            *vruntime += u64::from(slice) * NICE_0_VRUNTIME * NICE_WEIGHTS[20] / weight(&job.task);
+
+            if job.remaining == 0 {
+                ready.swap_remove(i);
+            }
+            if let Some(least) = ready.iter().map(|&(vruntime, _)| vruntime).min() {
+                min_vruntime = min_vruntime.max(least);
+            }
+        }
+
+        stitch(pieces, &originals)
+    }
+
+    fn capabilities(&self) -> PolicyCapabilities {
+        PolicyCapabilities {
+            preemptive: true,
+            weights: true,
+            deterministic: true,
+            ..Default::default()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn spans(schedule: &Schedule) -> Vec<(u64, u32, u32)> {
+        schedule
+            .segments
+            .iter()
+            .map(|s| (s.id, s.start, s.end))
+            .collect()
+    }
+
+    /// How long task `id` held the CPU before `until`.
+    fn cpu_time(schedule: &Schedule, id: u64, until: u32) -> u32 {
+        schedule
+            .segments
+            .iter()
+            .filter(|s| s.id == id && s.start < until)
+            .map(|s| s.end.min(until) - s.start)
+            .sum()
+    }
+
+    #[test]
+    fn equally_nice_tasks_take_turns() {
+        let tasks = vec![
+            Task {
+                id: 1,
+                execution_duration: 3,
+                ..Default::default()
+            },
+            Task {
+                id: 2,
+                execution_duration: 3,
+                ..Default::default()
+            },
+        ];
+
+        let schedule = FairScheduler { slice: 1 }.schedule(tasks);
+
+        assert_eq!(
+            spans(&schedule),
+            vec![
+                (1, 0, 1),
+                (2, 1, 2),
+                (1, 2, 3),
+                (2, 3, 4),
+                (1, 4, 5),
+                (2, 5, 6)
+            ]
+        );
+    }
+
+    #[test]
+    fn ties_go_to_the_earlier_arrival() {
+        // 0: #2 is started alone
+        // 1: #1 is queued level with #2, which got here first and keeps the CPU
+        // 2: #1 is started, #2 is ahead again
+        // then the tie at 3 goes to #2 as well
+        let tasks = vec![
+            Task {
+                id: 1,
+                queued_at: 1,
+                execution_duration: 2,
+                ..Default::default()
+            },
+            Task {
+                id: 2,
+                queued_at: 0,
+                execution_duration: 3,
+                ..Default::default()
+            },
+        ];
+
+        let schedule = FairScheduler { slice: 1 }.schedule(tasks);
+
+        assert_eq!(
+            spans(&schedule),
+            vec![(2, 0, 2), (1, 2, 3), (2, 3, 4), (1, 4, 5)]
+        );
+    }
+
+    #[test]
+    fn share_follows_weight() {
+        // nice 0 weighs 1024 against nice 5's 335, about three to one
+        let tasks = vec![
+            Task {
+                id: 1,
+                execution_duration: 100,
+                ..Default::default()
+            },
+            Task {
+                id: 2,
+                execution_duration: 100,
+                nice: 5,
+                ..Default::default()
+            },
+        ];
+
+        let schedule = FairScheduler::default().schedule(tasks);
+
+        assert_eq!(cpu_time(&schedule, 1, 80), 60);
+        assert_eq!(cpu_time(&schedule, 2, 80), 20);
+    }
+
+    #[test]
+    fn late_arrival_does_not_take_over() {
+        // 0: #1 is started alone
+        // 10: #2 is queued level with #1 and they take turns, two at a time
+        let tasks = vec![
+            Task {
+                id: 1,
+                execution_duration: 14,
+                ..Default::default()
+            },
+            Task {
+                id: 2,
+                queued_at: 10,
+                execution_duration: 4,
+                ..Default::default()
+            },
+        ];
+
+        let schedule = FairScheduler { slice: 2 }.schedule(tasks);
+
+        assert_eq!(
+            spans(&schedule),
+            vec![(1, 0, 12), (2, 12, 14), (1, 14, 16), (2, 16, 18)]
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "task 1 has nice 20, outside -20 to 19")]
+    fn nice_out_of_range() {
+        FairScheduler::default().schedule(vec![Task {
+            id: 1,
+            execution_duration: 1,
+            nice: 20,
+            ..Default::default()
+        }]);
+    }
+}
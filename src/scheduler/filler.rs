@@ -0,0 +1,243 @@
+// Background filler work squeezed into the gaps of another policy's schedule.
+use super::{PolicyCapabilities, Schedule, ScheduleEvent, ScheduledTask, Scheduler, Segment};
+use crate::Task;
+
+/// What happens to a filler task's progress when real work takes the CPU from it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FillerProgress {
+    /// It carries on where it left off in the next idle stretch.
+    #[default]
+    Resume,
+    /// The work done so far is lost, and it starts over in the next idle stretch.
+    Discard,
+}
+
+/// Runs the real work under a single-CPU `policy` exactly as it would run alone, and fills the
+/// time that leaves idle with the `filler` tasks. Fillers run one at a time in the order they
+/// become ready (ties by input order), and are preempted the moment a real task starts. Only
+/// their `queued_at` and `not_before` are honoured.
+pub struct FillerScheduler {
+    policy: Box<dyn Scheduler>,
+    progress: FillerProgress,
+}
+
+impl FillerScheduler {
+    pub fn new(policy: Box<dyn Scheduler>, progress: FillerProgress) -> Self {
+        FillerScheduler { policy, progress }
+    }
+}
+
+struct Filler {
+    task: Task,
+    remaining: u32,
+    first_started: Option<u32>,
+    preempted: bool,
+    finished_at: Option<u32>,
+}
+
+impl Scheduler for FillerScheduler {
+    fn schedule(&self, tasks: Vec<Task>) -> Schedule {
+        let (mut fillers, real): (Vec<Task>, Vec<Task>) =
+            tasks.into_iter().partition(|task| task.filler);
+        fillers.sort_by_key(|task| task.ready_at());
+        let mut fillers: Vec<Filler> = fillers
+            .into_iter()
+            .map(|task| Filler {
+                remaining: task.execution_duration,
+                task,
+                first_started: None,
+                preempted: false,
+                finished_at: None,
+            })
+            .collect();
+
+        let Schedule {
+            mut runs,
+            mut segments,
+            mut events,
+        } = self.policy.schedule(real);
+
+        // idle stretches, each ended by the real task that takes the CPU back
+        let mut gaps = vec![];
+        let mut free_from = 0;
+        for segment in &segments {
+            if segment.start > free_from {
+                gaps.push((free_from, segment.start, Some(segment.id)));
+            }
+            free_from = free_from.max(segment.end);
+        }
+        gaps.push((free_from, u32::MAX, None));
+
+        let mut filled = vec![];
+        for (start, end, by) in gaps {
+            let mut time = start;
+            while time < end {
+                let next = fillers
+                    .iter_mut()
+                    .filter(|f| f.finished_at.is_none())
+                    .min_by_key(|f| f.task.ready_at().max(time));
+                let filler = match next {
+                    Some(f) if f.task.ready_at().max(time) < end => f,
+                    _ => break,
+                };
+                time = time.max(filler.task.ready_at());
+                if filler.preempted {
+                    events.push(ScheduleEvent::Resumed {
+                        time,
+                        id: filler.task.id,
+                    });
+                }
+                filler.first_started.get_or_insert(time);
+
+                let stop = end.min(time + filler.remaining);
+                if stop > time {
+                    filled.push(Segment {
+                        id: filler.task.id,
+                        core: 0,
+                        start: time,
+                        end: stop,
+                    });
+                }
+                if time + filler.remaining <= end {
+                    filler.finished_at = Some(stop);
+                } else {
+                    filler.preempted = true;
+                    filler.remaining = match self.progress {
+                        FillerProgress::Resume => filler.remaining - (stop - time),
+                        FillerProgress::Discard => filler.task.execution_duration,
+                    };
+                    events.push(ScheduleEvent::Preempted {
+                        time: stop,
+                        id: filler.task.id,
+                        by: by.unwrap(),
+                    });
+                }
+                time = stop;
+            }
+        }
+
+        runs.extend(
+            fillers.into_iter().map(|f| {
+                ScheduledTask::new(f.task, f.first_started.unwrap(), f.finished_at.unwrap())
+            }),
+        );
+        runs.sort_by_key(|run| run.finished_at);
+        segments.extend(filled);
+        segments.sort_by_key(|segment| segment.start);
+        events.sort_by_key(|event| match *event {
+            ScheduleEvent::Preempted { time, .. } => (time, 0),
+            ScheduleEvent::Resumed { time, .. } => (time, 1),
+        });
+
+        Schedule {
+            runs,
+            segments,
+            events,
+        }
+    }
+
+    fn capabilities(&self) -> PolicyCapabilities {
+        PolicyCapabilities {
+            preemptive: true,
+            fillers: true,
+            ..self.policy.capabilities()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::SjfScheduler;
+
+    fn task(id: u64, queued_at: u32, execution_duration: u32, filler: bool) -> Task {
+        Task {
+            id,
+            queued_at,
+            execution_duration,
+            filler,
+            ..Default::default()
+        }
+    }
+
+    fn workload() -> Vec<Task> {
+        vec![
+            task(1, 0, 2, false),
+            task(2, 5, 2, false),
+            task(8, 0, 4, true),
+            task(9, 1, 1, true),
+        ]
+    }
+
+    fn schedule(progress: FillerProgress) -> Schedule {
+        FillerScheduler::new(Box::new(SjfScheduler::default()), progress).schedule(workload())
+    }
+
+    #[test]
+    fn fillers_use_idle_time_and_resume() {
+        // 0: #1 is started, fillers wait
+        // 2: #1 is finished, #8 is started
+        // 5: #8 is preempted by #2 with 1 left
+        // 7: #2 is finished, #8 is resumed
+        // 8: #8 is finished, #9 is started
+        let schedule = schedule(FillerProgress::Resume);
+
+        let spans: Vec<(u64, u32, u32)> = schedule
+            .segments
+            .iter()
+            .map(|s| (s.id, s.start, s.end))
+            .collect();
+        assert_eq!(
+            spans,
+            vec![(1, 0, 2), (8, 2, 5), (2, 5, 7), (8, 7, 8), (9, 8, 9)]
+        );
+        assert_eq!(schedule.order(), vec![1, 2, 8, 9]);
+        assert_eq!(
+            schedule.events,
+            vec![
+                ScheduleEvent::Preempted {
+                    time: 5,
+                    id: 8,
+                    by: 2
+                },
+                ScheduleEvent::Resumed { time: 7, id: 8 },
+            ]
+        );
+    }
+
+    #[test]
+    fn real_work_is_unaffected() {
+        let real: Vec<Task> = workload().into_iter().filter(|t| !t.filler).collect();
+        let alone = SjfScheduler::default().schedule(real);
+
+        let schedule = schedule(FillerProgress::Discard);
+
+        let real_runs: Vec<&ScheduledTask> = schedule
+            .runs
+            .iter()
+            .filter(|run| !run.task.filler)
+            .collect();
+        assert_eq!(real_runs, alone.runs.iter().collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn discarded_progress_starts_over() {
+        // #8 loses its 3 units of work at 5 and reruns all 4 from 7
+        let schedule = schedule(FillerProgress::Discard);
+
+        let run = schedule.runs.iter().find(|run| run.task.id == 8).unwrap();
+        assert_eq!((run.started_at, run.finished_at), (2, 11));
+    }
+
+    #[test]
+    fn check_rejects_fillers_elsewhere() {
+        assert_eq!(
+            SjfScheduler::default()
+                .capabilities()
+                .check(&workload())
+                .unwrap_err()
+                .field,
+            "filler"
+        );
+    }
+}
@@ -0,0 +1,197 @@
+// Lottery: every dispatch is a draw among the ready tasks, weighted by the tickets each holds.
+use super::{Job, PolicyCapabilities, Schedule, ScheduledTask, Scheduler};
+use crate::rng::SplitMix64;
+use crate::Task;
+
+/// Tickets handed to a task holding a single second of work under `Tickets::Duration`.
+const DURATION_TICKETS: u64 = 1 << 20;
+
+/// How many lottery tickets a task holds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Tickets {
+    /// `priority + 1` tickets; a task without a priority holds one.
+    #[default]
+    Priority,
+    /// Tickets inversely proportional to `execution_duration`, so a task half as long is twice
+    /// as likely to win.
+    Duration,
+}
+
+impl Tickets {
+    fn of(self, task: &Task) -> u64 {
+        match self {
+            Tickets::Priority => task.priority.map_or(1, |priority| u64::from(priority) + 1),
+            Tickets::Duration => {
+                (DURATION_TICKETS / u64::from(task.execution_duration.max(1))).max(1)
+            }
+        }
+    }
+}
+
+/// Non-preemptive lottery scheduling on a single CPU: whenever the CPU is idle, one of the
+/// tickets held by the ready tasks is drawn, and the task holding it runs to completion. Draws
+/// come from a PRNG seeded with `seed`, so the same seed always gives the same schedule.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct LotteryScheduler {
+    pub tickets: Tickets,
+    pub seed: u64,
+}
+
+impl Scheduler for LotteryScheduler {
+    fn schedule(&self, tasks: Vec<Task>) -> Schedule {
+        let mut rng = SplitMix64::stream(self.seed, "lottery");
+        let mut pending = Job::pending(tasks);
+        // in submission order, so a draw never depends on how ties happened to be sorted
+        let mut ready: Vec<(u64, Task)> = vec![];
+        let mut runs = vec![];
+        let mut time = 0_u32;
+
+        loop {
+            while pending
+                .last()
+                .is_some_and(|job| job.task.ready_at() <= time)
+            {
+                let task = pending.pop().unwrap().task;
+                ready.push((self.tickets.of(&task), task));
+            }
+
+            if ready.is_empty() {
+                // idle until the next arrival, if there is one
+                match pending.last() {
+                    Some(job) => time = job.task.ready_at(),
+                    None => break,
+                }
+                continue;
+            }
+
+            let total: u64 = ready.iter().map(|&(tickets, _)| tickets).sum();
+            let mut ticket = rng.below(total);
+            let winner = ready
+                .iter()
+                .position(|&(tickets, _)| match ticket.checked_sub(tickets) {
+                    Some(rest) => {
+                        ticket = rest;
+                        false
+                    }
+                    None => true,
+                })
+                .unwrap();
+
+            let (_, task) = ready.remove(winner);
+            let started_at = time;
+            time += task.execution_duration;
+            runs.push(ScheduledTask::new(task, started_at, time));
+        }
+
+        Schedule::from_runs(runs)
+    }
+
+    fn capabilities(&self) -> PolicyCapabilities {
+        PolicyCapabilities {
+            priorities: self.tickets == Tickets::Priority,
+            deterministic: true,
+            ..Default::default()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn contenders() -> Vec<Task> {
+        // #1 keeps the CPU busy while #2 and #3 queue up
+        vec![
+            Task {
+                id: 1,
+                queued_at: 0,
+                execution_duration: 1,
+                ..Default::default()
+            },
+            Task {
+                id: 2,
+                queued_at: 0,
+                execution_duration: 9,
+                priority: Some(8),
+                ..Default::default()
+            },
+            Task {
+                id: 3,
+                queued_at: 0,
+                execution_duration: 1,
+                ..Default::default()
+            },
+        ]
+    }
+
+    /// How many of `seeds` draw #2 before #3.
+    fn wins_for_2(tickets: Tickets, seeds: u64) -> u64 {
+        (0..seeds)
+            .filter(|&seed| {
+                let order = LotteryScheduler { tickets, seed }
+                    .schedule(contenders())
+                    .order();
+                order.iter().position(|&id| id == 2) < order.iter().position(|&id| id == 3)
+            })
+            .count() as u64
+    }
+
+    #[test]
+    fn same_seed_same_schedule() {
+        let tasks: Vec<Task> = (0..50)
+            .map(|id| Task {
+                id,
+                queued_at: 0,
+                execution_duration: 1 + id as u32 % 7,
+                ..Default::default()
+            })
+            .collect();
+        let lottery = |seed| {
+            LotteryScheduler {
+                tickets: Tickets::Duration,
+                seed,
+            }
+            .schedule(tasks.clone())
+        };
+
+        assert_eq!(lottery(7), lottery(7));
+        assert_ne!(lottery(7).order(), lottery(8).order());
+    }
+
+    #[test]
+    fn priority_tickets_favour_higher_priorities() {
+        // #2 holds 9 tickets against #3's one
+        let wins = wins_for_2(Tickets::Priority, 1000);
+        assert!((850..950).contains(&wins), "{} wins", wins);
+    }
+
+    #[test]
+    fn duration_tickets_favour_shorter_tasks() {
+        // #2 is nine times as long as #3, so holds a ninth of the tickets
+        let wins = wins_for_2(Tickets::Duration, 1000);
+        assert!((50..150).contains(&wins), "{} wins", wins);
+    }
+
+    #[test]
+    fn lone_tasks_run_as_they_arrive() {
+        let tasks = vec![
+            Task {
+                id: 1,
+                queued_at: 0,
+                execution_duration: 2,
+                ..Default::default()
+            },
+            Task {
+                id: 2,
+                queued_at: 5,
+                execution_duration: 2,
+                ..Default::default()
+            },
+        ];
+
+        let schedule = LotteryScheduler::default().schedule(tasks);
+
+        assert_eq!(schedule.order(), vec![1, 2]);
+        assert_eq!(schedule.runs[1].started_at, 5);
+    }
+}
@@ -0,0 +1,271 @@
+// Multilevel feedback queue: tasks that use up their time slices sink to lower priority levels.
+use super::switching::stitch;
+use super::{Job, PolicyCapabilities, Schedule, Scheduler, Segment};
+use crate::Task;
+use std::collections::{BTreeMap, VecDeque};
+
+/// A classic multilevel feedback queue on a single CPU:
+///
+/// - every task arrives at the top level;
+/// - the highest non-empty level runs, round robin within the level, and a task arriving at a
+///   higher level preempts one running lower down;
+/// - a task that has run for its level's quantum in total, across preemptions, moves down a
+///   level (the bottom level is plain round robin);
+/// - every `boost_period`, if set, every task goes back to the top level, so long tasks
+///   can't starve.
+///
+/// `quanta` holds each level's quantum, from the top level down. A task preempted by a higher
+/// level goes back to the front of its own level; as in `RoundRobinScheduler`, tasks arriving
+/// the moment a quantum expires queue ahead of the task it expired on. Task ids must be unique.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MlfqScheduler {
+    pub quanta: Vec<u32>,
+    pub boost_period: Option<u32>,
+}
+
+impl MlfqScheduler {
+    pub fn new(quanta: Vec<u32>) -> Self {
+        MlfqScheduler {
+            quanta,
+            boost_period: None,
+        }
+    }
+
+    /// Moves every task back to the top level every `period`.
+    pub fn boost_every(self, period: u32) -> Self {
+        MlfqScheduler {
+            boost_period: Some(period),
+            ..self
+        }
+    }
+}
+
+/// A job waiting at or running on a level, with how much of the level's quantum it has used.
+struct Entry {
+    job: Job,
+    used: u32,
+}
+
+/// Moves the jobs ready by `time` from `pending` onto the back of the top level.
+fn admit(pending: &mut Vec<Job>, top: &mut VecDeque<Entry>, time: u32) {
+    while pending
+        .last()
+        .is_some_and(|job| job.task.ready_at() <= time)
+    {
+        let job = pending.pop().unwrap();
+        top.push_back(Entry { job, used: 0 });
+    }
+}
+
+impl Scheduler for MlfqScheduler {
+    fn schedule(&self, tasks: Vec<Task>) -> Schedule {
+        assert!(!self.quanta.is_empty(), "need at least one level");
+        assert!(
+            self.quanta.iter().all(|&q| q > 0),
+            "quanta must be positive"
+        );
+        assert!(
+            self.boost_period != Some(0),
+            "boost period must be positive"
+        );
+
+        let mut originals = BTreeMap::new();
+        for task in &tasks {
+            assert!(
+                originals.insert(task.id, task.clone()).is_none(),
+                "task {} appears more than once",
+                task.id
+            );
+        }
+
+        let bottom = self.quanta.len() - 1;
+        let mut pending = Job::pending(tasks);
+        let mut levels: Vec<VecDeque<Entry>> =
+            self.quanta.iter().map(|_| VecDeque::new()).collect();
+        let mut running: Option<(Entry, usize)> = None;
+        let mut next_boost = self.boost_period;
+        let mut pieces = vec![];
+        let mut time = 0_u32;
+
+        loop {
+            admit(&mut pending, &mut levels[0], time);
+
+            while let Some(at) = next_boost.filter(|&at| at <= time) {
+                let mut top: VecDeque<Entry> =
+                    levels.iter_mut().flat_map(|l| l.drain(..)).collect();
+                for entry in &mut top {
+                    entry.used = 0;
+                }
+                levels[0] = top;
+                if let Some((entry, level)) = running.as_mut() {
+                    entry.used = 0;
+                    *level = 0;
+                }
+                next_boost = Some(at + self.boost_period.unwrap());
+            }
+
+            // a task waiting higher up takes the CPU
+            if let Some((entry, level)) = running.take() {
+                if levels[..level].iter().any(|l| !l.is_empty()) {
+                    levels[level].push_front(entry);
+                } else {
+                    running = Some((entry, level));
+                }
+            }
+
+            if running.is_none() {
+                running = levels
+                    .iter_mut()
+                    .enumerate()
+                    .find_map(|(level, l)| l.pop_front().map(|entry| (entry, level)));
+            }
+            let (mut entry, level) = match running.take() {
+                Some(running) => running,
+                // idle until the next arrival, if there is one
+                None => match pending.last() {
+                    Some(job) => {
+                        time = job.task.ready_at();
+                        continue;
+                    }
+                    None => break,
+                },
+            };
+
+            // run to the end of the quantum, or until the task finishes, the next arrival or
+            // the next boost, whichever comes first
+            let mut slice = entry.job.remaining.min(self.quanta[level] - entry.used);
+            if let Some(job) = pending.last() {
+                slice = slice.min(job.task.ready_at() - time);
+            }
+            if let Some(at) = next_boost {
+                slice = slice.min(at - time);
+            }
+            pieces.push(Segment {
+                id: entry.job.task.id,
+                core: 0,
+                start: time,
+                end: time + slice,
+            });
+            time += slice;
+            entry.job.remaining -= slice;
+            entry.used += slice;
+
+            if entry.job.remaining == 0 {
+                continue;
+            }
+            if entry.used == self.quanta[level] {
+                admit(&mut pending, &mut levels[0], time);
+                entry.used = 0;
+                levels[(level + 1).min(bottom)].push_back(entry);
+            } else {
+                running = Some((entry, level));
+            }
+        }
+
+        stitch(pieces, &originals)
+    }
+
+    fn capabilities(&self) -> PolicyCapabilities {
+        PolicyCapabilities {
+            preemptive: true,
+            deterministic: true,
+            ..Default::default()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scheduler::ScheduleEvent;
+    use crate::RoundRobinScheduler;
+
+    fn task(id: u64, queued_at: u32, execution_duration: u32) -> Task {
+        Task {
+            id,
+            queued_at,
+            execution_duration,
+            ..Default::default()
+        }
+    }
+
+    fn spans(schedule: &Schedule) -> Vec<(u64, u32, u32)> {
+        schedule
+            .segments
+            .iter()
+            .map(|s| (s.id, s.start, s.end))
+            .collect()
+    }
+
+    #[test]
+    fn long_task_sinks_and_short_one_gets_in() {
+        // 0: #1 is started at the top level
+        // 2: #1 used its quantum of 2, drops to the middle level and carries on
+        // 3: #2 is queued at the top level, preempting #1 with 1 of its 4 used
+        // 5: #2 is finished, #1 is resumed
+        // 8: #1 drops to the bottom level and carries on alone
+        let tasks = vec![task(1, 0, 20), task(2, 3, 2)];
+
+        let schedule = MlfqScheduler::new(vec![2, 4, 8]).schedule(tasks);
+
+        assert_eq!(spans(&schedule), vec![(1, 0, 3), (2, 3, 5), (1, 5, 22)]);
+        assert_eq!(
+            schedule.events,
+            vec![
+                ScheduleEvent::Preempted {
+                    time: 3,
+                    id: 1,
+                    by: 2,
+                },
+                ScheduleEvent::Resumed { time: 5, id: 1 },
+            ]
+        );
+    }
+
+    #[test]
+    fn tasks_on_a_level_take_turns() {
+        // both drop a level at 4 and 5, then alternate with quanta of 3
+        let tasks = vec![task(1, 0, 6), task(2, 0, 6)];
+
+        let schedule = MlfqScheduler::new(vec![2, 3]).schedule(tasks);
+
+        assert_eq!(
+            spans(&schedule),
+            vec![
+                (1, 0, 2),
+                (2, 2, 4),
+                (1, 4, 7),
+                (2, 7, 10),
+                (1, 10, 11),
+                (2, 11, 12)
+            ]
+        );
+    }
+
+    #[test]
+    fn boost_brings_a_sunk_task_back() {
+        // #1 sinks a level at 2, behind a stream of short arrivals that keeps the top level
+        // busy until 40; each boost gives it another quantum at the top
+        let mut tasks = vec![task(1, 0, 6)];
+        tasks.extend((2..=40).map(|id| task(id, id as u32 - 1, 1)));
+
+        let starved = MlfqScheduler::new(vec![2, 4]).schedule(tasks.clone());
+        let boosted = MlfqScheduler::new(vec![2, 4])
+            .boost_every(10)
+            .schedule(tasks);
+
+        let finish = |s: &Schedule| s.runs.iter().find(|r| r.task.id == 1).unwrap().finished_at;
+        assert_eq!(finish(&starved), 45);
+        assert_eq!(finish(&boosted), 26);
+    }
+
+    #[test]
+    fn single_level_is_round_robin() {
+        let tasks = vec![task(1, 0, 5), task(2, 1, 3), task(3, 2, 4)];
+
+        assert_eq!(
+            MlfqScheduler::new(vec![2]).schedule(tasks.clone()).order(),
+            RoundRobinScheduler { quantum: 2 }.schedule(tasks).order()
+        );
+    }
+}
@@ -0,0 +1,859 @@
+// Scheduling policies. Each implements `Scheduler`, so callers can swap one policy for another
+// (or plug in their own) without changing how workloads are run.
+use crate::dag::DependencyError;
+use crate::events::{self, Event};
+use crate::sim::{Config, Projection, Simulation};
+use crate::Task;
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+use std::sync::Arc;
+
+mod aging;
+mod batch;
+mod cost;
+mod credit;
+mod decay;
+mod edf;
+mod fair;
+mod filler;
+mod lottery;
+mod mlfq;
+mod multi_core;
+mod partitioned;
+mod priority;
+mod round_robin;
+mod srtf;
+mod switching;
+
+pub use aging::AgingScheduler;
+pub use batch::BatchScheduler;
+pub use cost::CostOfDelayScheduler;
+pub use credit::{CreditScheduler, CreditState};
+pub use decay::DecayScheduler;
+pub use edf::EdfScheduler;
+pub use fair::FairScheduler;
+pub use filler::{FillerProgress, FillerScheduler};
+pub use lottery::{LotteryScheduler, Tickets};
+pub use mlfq::MlfqScheduler;
+pub use multi_core::MultiCoreScheduler;
+pub use partitioned::PartitionedScheduler;
+pub use priority::PriorityScheduler;
+pub use round_robin::RoundRobinScheduler;
+pub use srtf::SrtfScheduler;
+pub use switching::SwitchingScheduler;
+
+/// When each task in a workload ran.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Schedule {
+    /// One entry per task, in completion order.
+    pub runs: Vec<ScheduledTask>,
+    /// Each stretch of time a task held the CPU, in time order. A task that was never preempted
+    /// has a single segment matching its run.
+    pub segments: Vec<Segment>,
+    /// Preemptions and resumptions, in time order. Empty for non-preemptive policies.
+    pub events: Vec<ScheduleEvent>,
+}
+
+/// A task as it ran: from when it first started to when it finished.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ScheduledTask {
+    pub task: Task,
+    pub started_at: u32,
+    pub finished_at: u32,
+    /// Time from when the task became ready to when it first started.
+    pub wait_time: u32,
+}
+
+impl ScheduledTask {
+    pub fn new(task: Task, started_at: u32, finished_at: u32) -> Self {
+        ScheduledTask {
+            wait_time: started_at.saturating_sub(task.ready_at()),
+            task,
+            started_at,
+            finished_at,
+        }
+    }
+
+    /// Whether the task finished after its `deadline`.
+    pub fn missed_deadline(&self) -> bool {
+        self.task.deadline.is_some_and(|d| self.finished_at > d)
+    }
+
+    /// How long after its `deadline` the task finished; 0 if on time or without one.
+    pub fn lateness(&self) -> u32 {
+        self.task
+            .deadline
+            .map_or(0, |d| self.finished_at.saturating_sub(d))
+    }
+
+    /// The task's `delay_cost` times how long it took, from becoming ready to finishing.
+    pub fn delay_cost(&self) -> u64 {
+        u64::from(self.task.delay_cost)
+            * u64::from(self.finished_at.saturating_sub(self.task.ready_at()))
+    }
+
+    pub fn projection(&self) -> Projection {
+        Projection {
+            id: self.task.id,
+            started_at: self.started_at,
+            finished_at: self.finished_at,
+        }
+    }
+}
+
+/// A task holding a CPU from `start` until `end`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Segment {
+    pub id: u64,
+    /// Which CPU the task ran on; always 0 for single-CPU policies.
+    pub core: usize,
+    pub start: u32,
+    pub end: u32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ScheduleEvent {
+    /// Task `id` was taken off the CPU at `time` so that task `by` could run.
+    Preempted { time: u32, id: u64, by: u64 },
+    /// Task `id`, previously preempted, got the CPU back at `time`.
+    Resumed { time: u32, id: u64 },
+}
+
+impl ScheduleEvent {
+    /// When the event happened.
+    pub fn time(&self) -> u32 {
+        match *self {
+            ScheduleEvent::Preempted { time, .. } | ScheduleEvent::Resumed { time, .. } => time,
+        }
+    }
+}
+
+impl Schedule {
+    /// A schedule for a non-preemptive single-CPU policy, where each run is a single segment.
+    pub fn from_runs(runs: Vec<ScheduledTask>) -> Schedule {
+        let mut segments: Vec<Segment> = runs
+            .iter()
+            .map(|run| Segment {
+                id: run.task.id,
+                core: 0,
+                start: run.started_at,
+                end: run.finished_at,
+            })
+            .collect();
+        segments.sort_by_key(|segment| segment.start);
+
+        Schedule {
+            runs,
+            segments,
+            events: vec![],
+        }
+    }
+
+    /// Task ids in the order they were completed.
+    pub fn order(&self) -> Vec<u64> {
+        self.runs.iter().map(|run| run.task.id).collect()
+    }
+
+    /// The schedule as JSON, for other tools to consume.
+    #[cfg(feature = "serde")]
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).expect("a schedule always serializes")
+    }
+
+    /// Everything that happened in the schedule, in time order; see `events::log`.
+    pub fn event_log(&self) -> Vec<Event> {
+        events::log(self)
+    }
+
+    /// Ids of tasks that finished after their deadline, in completion order.
+    pub fn missed_deadlines(&self) -> Vec<u64> {
+        self.runs
+            .iter()
+            .filter(|run| run.missed_deadline())
+            .map(|run| run.task.id)
+            .collect()
+    }
+
+    /// The cost of delay incurred across every run.
+    pub fn total_delay_cost(&self) -> u64 {
+        self.runs.iter().map(ScheduledTask::delay_cost).sum()
+    }
+
+    /// The runs as `Projection`s, e.g. for `ScheduleStats::from_projections`.
+    pub fn projections(&self) -> Vec<Projection> {
+        self.runs.iter().map(ScheduledTask::projection).collect()
+    }
+
+    /// The segments run on each core, indexed by core, each in time order. Cores that never
+    /// ran anything after the last busy one are not included.
+    pub fn timelines(&self) -> Vec<Vec<Segment>> {
+        let mut timelines: Vec<Vec<Segment>> = vec![];
+        for segment in &self.segments {
+            if timelines.len() <= segment.core {
+                timelines.resize(segment.core + 1, vec![]);
+            }
+            timelines[segment.core].push(*segment);
+        }
+        timelines
+    }
+}
+
+/// A task waiting for or holding the CPU in one of the preemptive policies.
+struct Job {
+    task: Task,
+    seq: u64,
+    remaining: u32,
+    first_started: Option<u32>,
+}
+
+impl Job {
+    /// Jobs for `tasks` numbered in input order, latest arrival first so the next one to arrive
+    /// can be popped off the end.
+    fn pending(tasks: Vec<Task>) -> Vec<Job> {
+        let mut pending: Vec<Job> = tasks
+            .into_iter()
+            .zip(0..)
+            .map(|(task, seq)| Job {
+                remaining: task.execution_duration,
+                task,
+                seq,
+                first_started: None,
+            })
+            .collect();
+        pending.sort_by_key(|job| (job.task.ready_at(), job.seq));
+        pending.reverse();
+        pending
+    }
+}
+
+/// Non-preemptive dispatch on a single CPU: whenever the CPU is idle, the ready task with the
+/// lowest `key(task, submission sequence number)` runs to completion.
+fn dispatch_by<K, F>(tasks: Vec<Task>, key: F) -> Schedule
+where
+    K: Ord,
+    F: Fn(&Task, u64) -> K,
+{
+    let mut pending = Job::pending(tasks);
+    let mut runs = vec![];
+    let mut time = 0_u32;
+    let mut ready = BTreeMap::new();
+
+    loop {
+        while pending
+            .last()
+            .is_some_and(|job| job.task.ready_at() <= time)
+        {
+            let Job { task, seq, .. } = pending.pop().unwrap();
+            ready.insert(key(&task, seq), task);
+        }
+
+        match ready.pop_first() {
+            Some((_, task)) => {
+                let started_at = time;
+                time += task.execution_duration;
+                runs.push(ScheduledTask::new(task, started_at, time));
+            }
+            // idle until the next arrival, if there is one
+            None => match pending.last() {
+                Some(job) => time = job.task.ready_at(),
+                None => break,
+            },
+        }
+    }
+
+    Schedule::from_runs(runs)
+}
+
+/// A scheduling policy: decides when each task in a workload runs.
+pub trait Scheduler {
+    fn schedule(&self, tasks: Vec<Task>) -> Schedule;
+
+    /// What the policy honours. A policy that doesn't say claims nothing, so `check` rejects any
+    /// workload relying on optional task fields.
+    fn capabilities(&self) -> PolicyCapabilities {
+        PolicyCapabilities::default()
+    }
+}
+
+/// Runs the tasks of `items` under `scheduler` and hands back each one's payload alongside its
+/// run, in completion order, so callers don't have to map ids back to their own data. Payloads
+/// of tasks that never ran are dropped. Panics if two tasks share an id.
+pub fn schedule_with_payloads<T>(
+    scheduler: &dyn Scheduler,
+    items: Vec<(Task, T)>,
+) -> Vec<(ScheduledTask, T)> {
+    let mut payloads = BTreeMap::new();
+    let mut tasks = Vec::with_capacity(items.len());
+    for (task, payload) in items {
+        assert!(
+            payloads.insert(task.id, payload).is_none(),
+            "task {} appears more than once",
+            task.id
+        );
+        tasks.push(task);
+    }
+
+    scheduler
+        .schedule(tasks)
+        .runs
+        .into_iter()
+        .map(|run| {
+            let payload = payloads.remove(&run.task.id).unwrap();
+            (run, payload)
+        })
+        .collect()
+}
+
+/// Which scheduling features a policy supports, so a workload it can't honour is rejected
+/// rather than having the fields it relies on silently ignored.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct PolicyCapabilities {
+    /// Running tasks may be taken off the CPU before they finish.
+    pub preemptive: bool,
+    /// Task priorities affect dispatch order.
+    pub priorities: bool,
+    /// Task deadlines affect dispatch order.
+    pub deadlines: bool,
+    /// `reserved_at` is honoured.
+    pub reservations: bool,
+    /// Tasks with `give_up_after` leave the queue once their patience runs out.
+    pub abandonment: bool,
+    /// Tasks wait for everything in their `depends_on` to finish.
+    pub dependencies: bool,
+    /// `filler` tasks only run in idle time.
+    pub fillers: bool,
+    /// A task's `nice` sets its share of the CPU.
+    pub weights: bool,
+    /// Starting a task costs its `cold_start` unless a warm slot matches.
+    pub cold_starts: bool,
+    /// Task `tag`s affect where or how tasks run.
+    pub tags: bool,
+    /// The same workload always produces the same schedule.
+    pub deterministic: bool,
+}
+
+/// A task relies on a field its policy would ignore.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Unsupported {
+    pub id: u64,
+    pub field: &'static str,
+}
+
+impl fmt::Display for Unsupported {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "task {} sets `{}`, which this policy does not support",
+            self.id, self.field
+        )
+    }
+}
+
+impl std::error::Error for Unsupported {}
+
+/// Why a workload can't be scheduled.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ScheduleError {
+    /// More than one task has this id.
+    DuplicateId {
+        id: u64,
+    },
+    /// Running every task back to back would take the clock past `u32::MAX` at this task.
+    TimeOverflow {
+        id: u64,
+    },
+    Dependency(DependencyError),
+}
+
+impl fmt::Display for ScheduleError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ScheduleError::DuplicateId { id } => write!(f, "task {} appears more than once", id),
+            ScheduleError::TimeOverflow { id } => {
+                write!(f, "task {} would finish after the end of time", id)
+            }
+            ScheduleError::Dependency(err) => err.fmt(f),
+        }
+    }
+}
+
+impl std::error::Error for ScheduleError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ScheduleError::Dependency(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<DependencyError> for ScheduleError {
+    fn from(err: DependencyError) -> Self {
+        ScheduleError::Dependency(err)
+    }
+}
+
+/// Checks `tasks` for anything that would make a schedule wrong rather than just slow: repeated
+/// ids, unsatisfiable dependencies, and times that don't fit in a `u32`. The overflow check runs
+/// the tasks first come first served. Any work-conserving single-CPU policy finishes at the same
+/// time, as its busy periods don't depend on the order.
+pub(crate) fn validate(tasks: &[Task]) -> Result<(), ScheduleError> {
+    let mut ids = BTreeSet::new();
+    for task in tasks {
+        if !ids.insert(task.id) {
+            return Err(ScheduleError::DuplicateId { id: task.id });
+        }
+    }
+    crate::dag::validate(tasks)?;
+
+    let mut by_start: Vec<&Task> = tasks.iter().collect();
+    by_start.sort_by_key(|task| task.reserved_start().unwrap_or_else(|| task.ready_at()));
+    let mut time = 0_u32;
+    for task in by_start {
+        let start = time.max(task.reserved_start().unwrap_or_else(|| task.ready_at()));
+        time = start
+            .checked_add(task.execution_duration)
+            .ok_or(ScheduleError::TimeOverflow { id: task.id })?;
+    }
+    Ok(())
+}
+
+impl PolicyCapabilities {
+    /// Fails on the first task using a field these capabilities can't honour.
+    pub fn check(&self, tasks: &[Task]) -> Result<(), Unsupported> {
+        for task in tasks {
+            let field = if task.reserved_at.is_some() && !self.reservations {
+                "reserved_at"
+            } else if task.give_up_after.is_some() && !self.abandonment {
+                "give_up_after"
+            } else if task.priority.is_some() && !self.priorities {
+                "priority"
+            } else if task.deadline.is_some() && !self.deadlines {
+                "deadline"
+            } else if !task.depends_on.is_empty() && !self.dependencies {
+                "depends_on"
+            } else if task.filler && !self.fillers {
+                "filler"
+            } else if task.nice != 0 && !self.weights {
+                "nice"
+            } else if task.cold_start > 0 && !self.cold_starts {
+                "cold_start"
+            } else if task.tag.is_some() && !self.tags {
+                "tag"
+            } else {
+                continue;
+            };
+            return Err(Unsupported { id: task.id, field });
+        }
+        Ok(())
+    }
+
+    /// What a policy made of two others supports: it preempts if either does, and honours a
+    /// task field only if both do.
+    pub(super) fn intersect(self, other: Self) -> Self {
+        PolicyCapabilities {
+            preemptive: self.preemptive || other.preemptive,
+            priorities: self.priorities && other.priorities,
+            deadlines: self.deadlines && other.deadlines,
+            reservations: self.reservations && other.reservations,
+            abandonment: self.abandonment && other.abandonment,
+            dependencies: self.dependencies && other.dependencies,
+            fillers: self.fillers && other.fillers,
+            weights: self.weights && other.weights,
+            cold_starts: self.cold_starts && other.cold_starts,
+            tags: self.tags && other.tags,
+            deterministic: self.deterministic && other.deterministic,
+        }
+    }
+}
+
+/// How ties are broken between ready tasks with the same `execution_duration`.
+///
+/// `execution_order` and `execution_order_original` have always disagreed here, so this makes
+/// the choice explicit rather than depending on which function is called. In the usual terms,
+/// `BTree` breaks ties by id, `Original` by queued-at time, `ArrivalOrder` is FIFO and `Lifo`
+/// is LIFO, while `Custom` takes a comparator.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub enum Semantics {
+    /// Lowest id runs first. This is what `execution_order` does, as its queue is keyed by
+    /// `(execution_duration, id)`.
+    #[default]
+    #[doc(alias = "ById")]
+    BTree,
+    /// The task that became ready earliest runs first, falling back to input order. This is what
+    /// `execution_order_original` does, as it takes the first shortest task from the list sorted
+    /// by `queued_at`.
+    #[doc(alias = "ByQueuedAt")]
+    Original,
+    /// The task submitted first runs first: input order, followed by spawned tasks in the order
+    /// they were spawned. Each task is given a sequence number at submission, so this holds no
+    /// matter when tasks are queued or how the input is sorted.
+    #[doc(alias = "Fifo")]
+    ArrivalOrder,
+    /// The task that became ready latest runs first, falling back to the one submitted last.
+    Lifo,
+    /// Whichever task the comparator puts first runs first, falling back to submission order.
+    Custom(TieComparator),
+}
+
+/// The comparator behind `Semantics::Custom`: it returns `Less` if the first task should run
+/// before the second. Two are equal only if they are clones of the same comparator.
+#[derive(Clone)]
+pub struct TieComparator(Arc<Compare>);
+
+type Compare = dyn Fn(&Task, &Task) -> Ordering + Send + Sync;
+
+impl TieComparator {
+    pub fn new<F>(compare: F) -> Self
+    where
+        F: Fn(&Task, &Task) -> Ordering + Send + Sync + 'static,
+    {
+        TieComparator(Arc::new(compare))
+    }
+}
+
+impl fmt::Debug for TieComparator {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("TieComparator(..)")
+    }
+}
+
+impl PartialEq for TieComparator {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl Eq for TieComparator {}
+
+/// Where a task ranks among ready tasks of equal length under some `Semantics`; lower runs
+/// first. Only ever compared against others from the same `Semantics`.
+#[derive(Debug, Clone)]
+pub(crate) enum Tie {
+    Key(u64),
+    /// Latest (ready time, sequence number) first.
+    Latest(u32, u64),
+    Custom(TieComparator, Task),
+}
+
+impl Tie {
+    /// The tie as a single number, lower first, for showing in traces. A comparator has no
+    /// such number, so every task it ranks shows 0.
+    pub(crate) fn rank(&self) -> u64 {
+        match *self {
+            Tie::Key(key) => key,
+            Tie::Latest(ready_at, _) => u64::MAX - u64::from(ready_at),
+            Tie::Custom(..) => 0,
+        }
+    }
+}
+
+impl Ord for Tie {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self, other) {
+            (Tie::Key(a), Tie::Key(b)) => a.cmp(b),
+            (Tie::Latest(a, a_seq), Tie::Latest(b, b_seq)) => (b, b_seq).cmp(&(a, a_seq)),
+            (Tie::Custom(TieComparator(compare), a), Tie::Custom(_, b)) => compare(a, b),
+            _ => Ordering::Equal,
+        }
+    }
+}
+
+impl PartialOrd for Tie {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl PartialEq for Tie {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Tie {}
+
+impl Semantics {
+    /// The secondary sort key for `task`, submitted as number `seq`, among ready tasks of equal
+    /// length. Ties on this key fall back to submission order.
+    pub(crate) fn tie_break(&self, task: &Task, seq: u64) -> Tie {
+        match self {
+            Semantics::BTree => Tie::Key(task.id),
+            Semantics::Original => Tie::Key(u64::from(task.ready_at())),
+            Semantics::ArrivalOrder => Tie::Key(0),
+            Semantics::Lifo => Tie::Latest(task.ready_at(), seq),
+            Semantics::Custom(compare) => Tie::Custom(compare.clone(), task.clone()),
+        }
+    }
+}
+
+/// Non-preemptive shortest job first on a single CPU: whenever the CPU is idle, it takes the
+/// ready task with the lowest `execution_duration`, breaking ties according to `semantics`.
+/// This is the policy behind `execution_order`. Tasks that give up waiting, and anything that
+/// depends on them, are left out of the schedule; `predict_abandonment` reports them.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct SjfScheduler {
+    pub semantics: Semantics,
+}
+
+impl SjfScheduler {
+    /// The runs of `schedule(tasks)` one at a time in completion order, simulated only as far as
+    /// they are consumed.
+    pub(crate) fn runs(&self, tasks: Vec<Task>) -> impl Iterator<Item = ScheduledTask> {
+        let config = Config {
+            semantics: self.semantics.clone(),
+            ..Default::default()
+        };
+        let mut simulation = Simulation::with_dependencies(tasks, |_, _| vec![], config)
+            .unwrap_or_else(|err| panic!("{}", err));
+        std::iter::from_fn(move || {
+            let (task, run) = simulation.next_run()?;
+            Some(ScheduledTask::new(task, run.started_at, run.finished_at))
+        })
+    }
+}
+
+impl Scheduler for SjfScheduler {
+    fn schedule(&self, tasks: Vec<Task>) -> Schedule {
+        Schedule::from_runs(self.runs(tasks).collect())
+    }
+
+    fn capabilities(&self) -> PolicyCapabilities {
+        PolicyCapabilities {
+            reservations: true,
+            abandonment: true,
+            dependencies: true,
+            deterministic: true,
+            ..Default::default()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sim::predict_finish_times;
+
+    fn tasks() -> Vec<Task> {
+        vec![
+            Task {
+                id: 42,
+                queued_at: 0,
+                execution_duration: 3,
+                ..Default::default()
+            },
+            Task {
+                id: 43,
+                queued_at: 1,
+                execution_duration: 3,
+                ..Default::default()
+            },
+            Task {
+                id: 44,
+                queued_at: 2,
+                execution_duration: 2,
+                ..Default::default()
+            },
+        ]
+    }
+
+    /// Runs tasks strictly in the order they were queued, to check that policies plug in.
+    struct Fifo;
+
+    impl Scheduler for Fifo {
+        fn schedule(&self, mut tasks: Vec<Task>) -> Schedule {
+            tasks.sort_by_key(|task| task.queued_at);
+            let mut time = 0;
+            let runs = tasks
+                .into_iter()
+                .map(|task| {
+                    let started_at = time.max(task.queued_at);
+                    time = started_at + task.execution_duration;
+                    ScheduledTask::new(task, started_at, time)
+                })
+                .collect();
+            Schedule::from_runs(runs)
+        }
+    }
+
+    #[test]
+    fn sjf_schedule_matches_prediction() {
+        let schedule = SjfScheduler::default().schedule(tasks());
+
+        assert_eq!(schedule.projections(), predict_finish_times(tasks()));
+        assert_eq!(schedule.order(), vec![42, 44, 43]);
+    }
+
+    #[test]
+    fn policies_are_interchangeable() {
+        let policies: Vec<Box<dyn Scheduler>> =
+            vec![Box::new(SjfScheduler::default()), Box::new(Fifo)];
+
+        let orders: Vec<Vec<u64>> = policies
+            .iter()
+            .map(|policy| policy.schedule(tasks()).order())
+            .collect();
+
+        assert_eq!(orders, vec![vec![42, 44, 43], vec![42, 43, 44]]);
+    }
+
+    #[test]
+    fn sjf_waits_for_dependencies() {
+        // #44 is shorter but can't start until #43 finishes at 6
+        let mut tasks = tasks();
+        tasks[2].depends_on = vec![43];
+
+        let schedule = SjfScheduler::default().schedule(tasks.clone());
+
+        assert_eq!(schedule.order(), vec![42, 43, 44]);
+        assert_eq!(schedule.runs[2].started_at, 6);
+        assert_eq!(
+            SrtfScheduler::default().capabilities().check(&tasks),
+            Err(Unsupported {
+                id: 44,
+                field: "depends_on",
+            })
+        );
+    }
+
+    #[test]
+    fn dependents_run_unchanged_in_arrival_order() {
+        let tasks = vec![
+            Task {
+                id: 7,
+                queued_at: 0,
+                execution_duration: 2,
+                depends_on: vec![9],
+                ..Default::default()
+            },
+            Task {
+                id: 8,
+                queued_at: 1,
+                execution_duration: 2,
+                ..Default::default()
+            },
+            Task {
+                id: 9,
+                queued_at: 0,
+                execution_duration: 1,
+                ..Default::default()
+            },
+        ];
+
+        let schedule = SjfScheduler {
+            semantics: Semantics::ArrivalOrder,
+        }
+        .schedule(tasks.clone());
+
+        // 0: #9 is started
+        // 1: #7 is released as #8 arrives; same duration, so #7 goes first as it was submitted
+        //    first
+        assert_eq!(schedule.order(), vec![9, 7, 8]);
+        assert_eq!(schedule.runs[1].task, tasks[0]);
+        assert_eq!(schedule.runs[1].started_at, 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "dependency cycle")]
+    fn sjf_panics_on_dependency_cycle() {
+        let mut tasks = tasks();
+        tasks[0].depends_on = vec![43];
+        tasks[1].depends_on = vec![42];
+
+        SjfScheduler::default().schedule(tasks);
+    }
+
+    #[test]
+    fn payloads_come_back_in_completion_order() {
+        let items: Vec<(Task, &str)> = tasks().into_iter().zip(["a", "b", "c"]).collect();
+
+        let runs = schedule_with_payloads(&SjfScheduler::default(), items);
+
+        let order: Vec<(u64, &str)> = runs.iter().map(|(run, p)| (run.task.id, *p)).collect();
+        assert_eq!(order, vec![(42, "a"), (44, "c"), (43, "b")]);
+    }
+
+    #[test]
+    #[should_panic(expected = "task 42 appears more than once")]
+    fn payloads_need_unique_ids() {
+        let task = tasks().remove(0);
+
+        schedule_with_payloads(&Fifo, vec![(task.clone(), 1), (task, 2)]);
+    }
+
+    #[test]
+    fn check_rejects_fields_the_policy_ignores() {
+        let mut tasks = tasks();
+        tasks[1].reserved_at = Some(5);
+
+        assert_eq!(SjfScheduler::default().capabilities().check(&tasks), Ok(()));
+        assert_eq!(
+            SrtfScheduler::default().capabilities().check(&tasks),
+            Err(Unsupported {
+                id: 43,
+                field: "reserved_at",
+            })
+        );
+        assert_eq!(
+            Fifo.capabilities().check(&tasks).unwrap_err().to_string(),
+            "task 43 sets `reserved_at`, which this policy does not support"
+        );
+    }
+
+    #[test]
+    fn check_flags_nice_cold_starts_and_tags() {
+        let mut tasks = tasks();
+        tasks[0].nice = 5;
+        tasks[1].cold_start = 2;
+        tasks[2].tag = Some(7);
+
+        let fields: Vec<&str> = tasks
+            .iter()
+            .map(|task| {
+                SjfScheduler::default()
+                    .capabilities()
+                    .check(std::slice::from_ref(task))
+                    .unwrap_err()
+                    .field
+            })
+            .collect();
+        assert_eq!(fields, vec!["nice", "cold_start", "tag"]);
+        assert_eq!(
+            FairScheduler::default().capabilities().check(&tasks[..1]),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn check_accepts_plain_workloads() {
+        for policy in [
+            Box::new(Fifo) as Box<dyn Scheduler>,
+            Box::new(SrtfScheduler::default()),
+            Box::new(RoundRobinScheduler { quantum: 1 }),
+        ] {
+            assert_eq!(policy.capabilities().check(&tasks()), Ok(()));
+        }
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn schedule_round_trips_through_json() {
+        let tasks = Task::from_json_slice(
+            br#"[{"id": 42, "execution_duration": 3}, {"id": 43, "queued_at": 1, "priority": 2}]"#,
+        )
+        .unwrap();
+        assert_eq!(tasks[1].priority, Some(2));
+        assert_eq!(tasks[1].execution_duration, 0);
+
+        let schedule = SrtfScheduler::default().schedule(tasks);
+        let json = schedule.to_json();
+
+        assert!(json.starts_with(r#"{"runs":[{"task":{"id":43,"#));
+        assert_eq!(serde_json::from_str::<Schedule>(&json).unwrap(), schedule);
+        assert!(Task::from_json_slice(b"[{\"id\": -1}]").is_err());
+    }
+}
@@ -0,0 +1,178 @@
+// Shortest job first across a pool of identical CPUs.
+use super::{Job, PolicyCapabilities, Schedule, ScheduledTask, Scheduler, Segment, Semantics};
+use crate::Task;
+use std::collections::BTreeMap;
+
+/// Non-preemptive shortest job first on `num_cpus` identical CPUs. Whenever a core is idle it
+/// takes the shortest ready task, breaking ties according to `semantics`; if several cores are
+/// idle at once, the lowest-numbered core is filled first. `runs` are in completion order, and
+/// `Schedule::timelines` gives what each core ran.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MultiCoreScheduler {
+    pub num_cpus: usize,
+    pub semantics: Semantics,
+}
+
+impl MultiCoreScheduler {
+    pub fn new(num_cpus: usize) -> Self {
+        assert!(num_cpus > 0, "need at least one CPU");
+        MultiCoreScheduler {
+            num_cpus,
+            semantics: Semantics::default(),
+        }
+    }
+}
+
+impl Scheduler for MultiCoreScheduler {
+    fn schedule(&self, tasks: Vec<Task>) -> Schedule {
+        let mut pending = Job::pending(tasks);
+        let mut schedule = Schedule::default();
+        let mut time = 0_u32;
+        let mut ready = BTreeMap::new();
+        // when each core next becomes free
+        let mut free_at = vec![0_u32; self.num_cpus];
+
+        loop {
+            while pending
+                .last()
+                .is_some_and(|job| job.task.ready_at() <= time)
+            {
+                let Job { task, seq, .. } = pending.pop().unwrap();
+                let key = (
+                    task.execution_duration,
+                    self.semantics.tie_break(&task, seq),
+                    seq,
+                );
+                ready.insert(key, task);
+            }
+
+            // a zero-length task frees its core straight away, so keep filling the lowest free
+            // core until every core is busy or nothing is left to run
+            while let Some(core) = free_at.iter().position(|&at| at <= time) {
+                let task = match ready.pop_first() {
+                    Some((_, task)) => task,
+                    None => break,
+                };
+                free_at[core] = time + task.execution_duration;
+                schedule.segments.push(Segment {
+                    id: task.id,
+                    core,
+                    start: time,
+                    end: free_at[core],
+                });
+                schedule
+                    .runs
+                    .push(ScheduledTask::new(task, time, free_at[core]));
+            }
+
+            // move on to the next arrival or core coming free, whichever is sooner
+            let next_free = free_at.iter().filter(|&&at| at > time).min().copied();
+            let next_arrival = pending.last().map(|job| job.task.ready_at());
+            match next_free.into_iter().chain(next_arrival).min() {
+                Some(next) => time = next,
+                None => break,
+            }
+        }
+
+        // dispatch order, stably re-sorted into completion order
+        schedule.runs.sort_by_key(|run| run.finished_at);
+        schedule
+    }
+
+    fn capabilities(&self) -> PolicyCapabilities {
+        PolicyCapabilities {
+            deterministic: true,
+            ..Default::default()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::SjfScheduler;
+
+    fn pool_workload() -> Vec<Task> {
+        // 1   0   4
+        // 2   0   2
+        // 3   0   3
+        // 4   1   1
+        [(1, 0, 4), (2, 0, 2), (3, 0, 3), (4, 1, 1)]
+            .iter()
+            .map(|&(id, queued_at, execution_duration)| Task {
+                id,
+                queued_at,
+                execution_duration,
+                ..Default::default()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn idle_cores_take_the_shortest_ready_tasks() {
+        // 0: #2 is started on core 0, #3 on core 1
+        // 1: #4 is queued
+        // 2: #2 is finished, #4 is started on core 0
+        // 3: #3 and #4 are finished, #1 is started on core 0 as the lowest free core
+        // 7: #1 is finished
+        let schedule = MultiCoreScheduler::new(2).schedule(pool_workload());
+
+        assert_eq!(schedule.order(), vec![2, 3, 4, 1]);
+        let timelines: Vec<Vec<(u64, u32, u32)>> = schedule
+            .timelines()
+            .iter()
+            .map(|core| core.iter().map(|s| (s.id, s.start, s.end)).collect())
+            .collect();
+        assert_eq!(
+            timelines,
+            vec![vec![(2, 0, 2), (4, 2, 3), (1, 3, 7)], vec![(3, 0, 3)]]
+        );
+    }
+
+    #[test]
+    fn one_cpu_matches_sjf() {
+        assert_eq!(
+            MultiCoreScheduler::new(1).schedule(pool_workload()),
+            SjfScheduler::default().schedule(pool_workload())
+        );
+    }
+
+    #[test]
+    fn spare_cores_stay_idle() {
+        let schedule = MultiCoreScheduler::new(8).schedule(pool_workload());
+
+        assert_eq!(schedule.timelines().len(), 4);
+        assert!(schedule.runs.iter().all(|run| run.wait_time == 0));
+    }
+
+    #[test]
+    fn zero_length_tasks_free_their_core_at_once() {
+        let tasks: Vec<Task> = [(1, 0, 0), (2, 0, 5), (3, 0, 2)]
+            .iter()
+            .map(|&(id, queued_at, execution_duration)| Task {
+                id,
+                queued_at,
+                execution_duration,
+                ..Default::default()
+            })
+            .collect();
+
+        let one = MultiCoreScheduler::new(1).schedule(tasks.clone());
+        assert_eq!(one, SjfScheduler::default().schedule(tasks.clone()));
+
+        // 0: #1 is done on core 0 as soon as it starts, so core 0 takes #3 and core 1 #2
+        let two = MultiCoreScheduler::new(2).schedule(tasks);
+        let starts: Vec<(u64, usize, u32)> = two
+            .segments
+            .iter()
+            .map(|s| (s.id, s.core, s.start))
+            .collect();
+        assert_eq!(starts, vec![(1, 0, 0), (3, 0, 0), (2, 1, 0)]);
+    }
+
+    #[test]
+    #[should_panic(expected = "need at least one CPU")]
+    fn no_cpus() {
+        MultiCoreScheduler::new(0);
+    }
+}
@@ -0,0 +1,213 @@
+// Partitioned CPUs: each group of cores runs its own policy over the tasks routed to it.
+use super::{PolicyCapabilities, Schedule, Scheduler, Segment};
+use crate::Task;
+use std::collections::BTreeMap;
+
+/// Splits the CPUs into partitions, each running its own policy, with tasks routed to a
+/// partition by their `tag`: say a round robin core for interactive work next to a pool of SJF
+/// batch cores. Tasks whose tag isn't routed anywhere go to the default partition.
+///
+/// Cores are numbered in partition order, starting with the default partition, so a segment's
+/// `core` is unique across the whole machine. Partitions share nothing, so a task in one never
+/// waits for a core in another, and `depends_on` can't cross partitions.
+pub struct PartitionedScheduler {
+    /// Each partition's core count and policy; the first is the default.
+    partitions: Vec<(usize, Box<dyn Scheduler>)>,
+    routes: BTreeMap<u64, usize>,
+}
+
+impl PartitionedScheduler {
+    /// A single default partition of `cores` cores running `policy`. `policy` must only use
+    /// cores `0..cores`.
+    pub fn new(cores: usize, policy: Box<dyn Scheduler>) -> Self {
+        assert!(cores > 0, "need at least one CPU");
+        PartitionedScheduler {
+            partitions: vec![(cores, policy)],
+            routes: BTreeMap::new(),
+        }
+    }
+
+    /// Adds a partition of `cores` cores running `policy` for the tasks tagged with any of
+    /// `tags`. Panics if a tag is already routed to another partition.
+    pub fn partition(mut self, tags: &[u64], cores: usize, policy: Box<dyn Scheduler>) -> Self {
+        assert!(cores > 0, "need at least one CPU");
+        let index = self.partitions.len();
+        for &tag in tags {
+            assert!(
+                self.routes.insert(tag, index).is_none(),
+                "tag {} is routed to more than one partition",
+                tag
+            );
+        }
+        self.partitions.push((cores, policy));
+        self
+    }
+
+    fn route(&self, task: &Task) -> usize {
+        task.tag
+            .and_then(|tag| self.routes.get(&tag).copied())
+            .unwrap_or(0)
+    }
+}
+
+impl Scheduler for PartitionedScheduler {
+    fn schedule(&self, tasks: Vec<Task>) -> Schedule {
+        let mut routed: Vec<Vec<Task>> = self.partitions.iter().map(|_| vec![]).collect();
+        for task in tasks {
+            routed[self.route(&task)].push(task);
+        }
+
+        let mut schedule = Schedule::default();
+        let mut first_core = 0;
+        for ((cores, policy), tasks) in self.partitions.iter().zip(routed) {
+            let part = policy.schedule(tasks);
+            for segment in part.segments {
+                assert!(
+                    segment.core < *cores,
+                    "partition of {} cores ran task {} on core {}",
+                    cores,
+                    segment.id,
+                    segment.core
+                );
+                schedule.segments.push(Segment {
+                    core: first_core + segment.core,
+                    ..segment
+                });
+            }
+            schedule.runs.extend(part.runs);
+            schedule.events.extend(part.events);
+            first_core += cores;
+        }
+
+        // partition order, stably re-sorted into time order
+        schedule.runs.sort_by_key(|run| run.finished_at);
+        schedule.segments.sort_by_key(|segment| segment.start);
+        schedule.events.sort_by_key(|event| event.time());
+        schedule
+    }
+
+    fn capabilities(&self) -> PolicyCapabilities {
+        // only what every partition supports, plus the tags tasks are routed by
+        let capabilities = self
+            .partitions
+            .iter()
+            .map(|(_, policy)| policy.capabilities())
+            .reduce(PolicyCapabilities::intersect)
+            .unwrap();
+        PolicyCapabilities {
+            tags: true,
+            ..capabilities
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{MultiCoreScheduler, RoundRobinScheduler, SjfScheduler};
+
+    const INTERACTIVE: u64 = 1;
+
+    fn mixed() -> Vec<Task> {
+        // a long batch job, two more queued behind it, and two interactive requests
+        [
+            (1, 0, 8, None),
+            (2, 0, 6, None),
+            (3, 1, 2, None),
+            (4, 1, 3, Some(INTERACTIVE)),
+            (5, 2, 1, Some(INTERACTIVE)),
+        ]
+        .iter()
+        .map(|&(id, queued_at, execution_duration, tag)| Task {
+            id,
+            queued_at,
+            execution_duration,
+            tag,
+            ..Default::default()
+        })
+        .collect()
+    }
+
+    fn spans(schedule: &Schedule) -> Vec<(u64, usize, u32, u32)> {
+        schedule
+            .segments
+            .iter()
+            .map(|s| (s.id, s.core, s.start, s.end))
+            .collect()
+    }
+
+    #[test]
+    fn tagged_tasks_get_their_own_cores() {
+        // cores 0 and 1 run SJF batch work, core 2 round robin for interactive requests
+        // 0: #2 is started on core 0, #1 on core 1
+        // 1: #4 is started on core 2
+        // 2: #4's quantum expires, #5 is started
+        // 3: #5 is finished, #4 is resumed
+        // 5: #4 is finished
+        // 6: #2 is finished, #3 is started on core 0
+        let scheduler = PartitionedScheduler::new(2, Box::new(MultiCoreScheduler::new(2)))
+            .partition(
+                &[INTERACTIVE],
+                1,
+                Box::new(RoundRobinScheduler { quantum: 1 }),
+            );
+
+        let schedule = scheduler.schedule(mixed());
+
+        assert_eq!(
+            spans(&schedule),
+            vec![
+                (2, 0, 0, 6),
+                (1, 1, 0, 8),
+                (4, 2, 1, 2),
+                (5, 2, 2, 3),
+                (4, 2, 3, 5),
+                (3, 0, 6, 8),
+            ]
+        );
+        assert_eq!(schedule.order(), vec![5, 4, 2, 1, 3]);
+        assert_eq!(schedule.events.len(), 2);
+    }
+
+    #[test]
+    fn unrouted_tags_go_to_the_default_partition() {
+        let scheduler = PartitionedScheduler::new(1, Box::new(SjfScheduler::default())).partition(
+            &[99],
+            1,
+            Box::new(SjfScheduler::default()),
+        );
+
+        assert_eq!(
+            scheduler.schedule(mixed()),
+            SjfScheduler::default().schedule(mixed())
+        );
+    }
+
+    #[test]
+    fn capabilities_are_those_every_partition_has() {
+        let scheduler = PartitionedScheduler::new(1, Box::new(SjfScheduler::default())).partition(
+            &[1],
+            1,
+            Box::new(RoundRobinScheduler { quantum: 1 }),
+        );
+
+        let capabilities = scheduler.capabilities();
+        assert!(capabilities.preemptive);
+        assert!(capabilities.tags);
+        assert!(!capabilities.dependencies);
+    }
+
+    #[test]
+    #[should_panic(expected = "tag 1 is routed to more than one partition")]
+    fn tag_routed_twice() {
+        PartitionedScheduler::new(1, Box::new(SjfScheduler::default()))
+            .partition(&[1], 1, Box::new(SjfScheduler::default()))
+            .partition(&[2, 1], 1, Box::new(SjfScheduler::default()));
+    }
+
+    #[test]
+    #[should_panic(expected = "partition of 1 cores ran task")]
+    fn policy_using_more_cores_than_its_partition() {
+        PartitionedScheduler::new(1, Box::new(MultiCoreScheduler::new(2))).schedule(mixed());
+    }
+}
@@ -0,0 +1,128 @@
+// Priority scheduling: the most important ready task runs next, shortest first among equals.
+use super::{dispatch_by, PolicyCapabilities, Schedule, Scheduler, Semantics};
+use crate::Task;
+use std::cmp::Reverse;
+
+/// Non-preemptive priority scheduling on a single CPU: whenever the CPU is idle, it takes the
+/// ready task with the highest `priority`, then the lowest `execution_duration`, then breaks
+/// ties according to `semantics`. Tasks without a priority rank below every task with one, so
+/// a workload with no priorities at all is scheduled exactly as `SjfScheduler` would.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct PriorityScheduler {
+    pub semantics: Semantics,
+}
+
+impl Scheduler for PriorityScheduler {
+    fn schedule(&self, tasks: Vec<Task>) -> Schedule {
+        dispatch_by(tasks, |task, seq| {
+            (
+                Reverse(task.priority),
+                task.execution_duration,
+                self.semantics.tie_break(task, seq),
+                seq,
+            )
+        })
+    }
+
+    fn capabilities(&self) -> PolicyCapabilities {
+        PolicyCapabilities {
+            priorities: true,
+            deterministic: true,
+            ..Default::default()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::SjfScheduler;
+
+    #[test]
+    fn highest_priority_runs_first() {
+        let tasks = vec![
+            Task {
+                id: 42,
+                queued_at: 0,
+                execution_duration: 1,
+                ..Default::default()
+            },
+            Task {
+                id: 43,
+                queued_at: 0,
+                execution_duration: 5,
+                priority: Some(1),
+                ..Default::default()
+            },
+            Task {
+                id: 44,
+                queued_at: 0,
+                execution_duration: 3,
+                priority: Some(9),
+                ..Default::default()
+            },
+            Task {
+                id: 45,
+                queued_at: 0,
+                execution_duration: 2,
+                priority: Some(1),
+                ..Default::default()
+            },
+        ];
+
+        let schedule = PriorityScheduler::default().schedule(tasks);
+
+        // #45 beats #43 on duration at equal priority; #42 has none so goes last
+        assert_eq!(schedule.order(), vec![44, 45, 43, 42]);
+    }
+
+    #[test]
+    fn does_not_preempt_for_a_more_important_arrival() {
+        let tasks = vec![
+            Task {
+                id: 42,
+                queued_at: 0,
+                execution_duration: 4,
+                ..Default::default()
+            },
+            Task {
+                id: 43,
+                queued_at: 1,
+                execution_duration: 1,
+                priority: Some(9),
+                ..Default::default()
+            },
+        ];
+
+        let schedule = PriorityScheduler::default().schedule(tasks);
+
+        assert_eq!(schedule.runs[1].started_at, 4);
+    }
+
+    #[test]
+    fn without_priorities_matches_sjf() {
+        let tasks: Vec<Task> = [(1, 0, 5), (9, 2, 3), (4, 3, 3), (7, 2, 3), (2, 20, 1)]
+            .iter()
+            .map(|&(id, queued_at, execution_duration)| Task {
+                id,
+                queued_at,
+                execution_duration,
+                ..Default::default()
+            })
+            .collect();
+
+        for semantics in [
+            Semantics::BTree,
+            Semantics::Original,
+            Semantics::ArrivalOrder,
+        ] {
+            assert_eq!(
+                PriorityScheduler {
+                    semantics: semantics.clone()
+                }
+                .schedule(tasks.clone()),
+                SjfScheduler { semantics }.schedule(tasks.clone())
+            );
+        }
+    }
+}
@@ -0,0 +1,215 @@
+// Round robin: every queued task gets the CPU in turn for at most one time quantum.
+use super::{Job, PolicyCapabilities, Schedule, ScheduleEvent, ScheduledTask, Scheduler, Segment};
+use crate::Task;
+use std::collections::VecDeque;
+
+/// Preemptive round robin on a single CPU. Tasks queue in arrival order (ties by input order)
+/// and each runs for at most `quantum` before going to the back of the queue. Tasks arriving
+/// while a quantum runs are queued ahead of the task it preempts. A task whose quantum expires
+/// with nobody waiting simply carries on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RoundRobinScheduler {
+    pub quantum: u32,
+}
+
+impl Scheduler for RoundRobinScheduler {
+    fn schedule(&self, tasks: Vec<Task>) -> Schedule {
+        assert!(self.quantum > 0, "quantum must be positive");
+
+        let mut pending = Job::pending(tasks);
+        let mut schedule = Schedule::default();
+        let mut time = 0_u32;
+        let mut queue: VecDeque<Job> = VecDeque::new();
+        let mut admit = |queue: &mut VecDeque<Job>, time: u32| {
+            while pending
+                .last()
+                .is_some_and(|job| job.task.ready_at() <= time)
+            {
+                queue.push_back(pending.pop().unwrap());
+            }
+            pending.last().map(|job| job.task.ready_at())
+        };
+
+        loop {
+            let next_arrival = admit(&mut queue, time);
+            let mut job = match queue.pop_front() {
+                Some(job) => job,
+                // idle until the next arrival, if there is one
+                None => match next_arrival {
+                    Some(at) => {
+                        time = at;
+                        continue;
+                    }
+                    None => break,
+                },
+            };
+
+            match job.first_started {
+                Some(_) => schedule.events.push(ScheduleEvent::Resumed {
+                    time,
+                    id: job.task.id,
+                }),
+                None => job.first_started = Some(time),
+            }
+
+            let start = time;
+            loop {
+                let slice = self.quantum.min(job.remaining);
+                time += slice;
+                job.remaining -= slice;
+                admit(&mut queue, time);
+
+                if job.remaining == 0 {
+                    schedule.segments.push(Segment {
+                        id: job.task.id,
+                        core: 0,
+                        start,
+                        end: time,
+                    });
+                    let started_at = job.first_started.unwrap();
+                    schedule
+                        .runs
+                        .push(ScheduledTask::new(job.task, started_at, time));
+                    break;
+                }
+                if let Some(next) = queue.front() {
+                    schedule.segments.push(Segment {
+                        id: job.task.id,
+                        core: 0,
+                        start,
+                        end: time,
+                    });
+                    schedule.events.push(ScheduleEvent::Preempted {
+                        time,
+                        id: job.task.id,
+                        by: next.task.id,
+                    });
+                    queue.push_back(job);
+                    break;
+                }
+            }
+        }
+
+        schedule
+    }
+
+    fn capabilities(&self) -> PolicyCapabilities {
+        PolicyCapabilities {
+            preemptive: true,
+            deterministic: true,
+            ..Default::default()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Projection;
+
+    fn interactive() -> Vec<Task> {
+        // 1   0   5
+        // 2   1   3
+        // 3   2   1
+        [(1, 0, 5), (2, 1, 3), (3, 2, 1)]
+            .iter()
+            .map(|&(id, queued_at, execution_duration)| Task {
+                id,
+                queued_at,
+                execution_duration,
+                ..Default::default()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn tasks_take_turns() {
+        // 0: #1 is started
+        // 2: #1's quantum expires, queue is #2, #3 then #1
+        // 4: #2's quantum expires, queue is #3, #1, #2
+        // 5: #3 is finished, #1 is resumed
+        // 7: #1's quantum expires, #2 is resumed
+        // 8: #2 is finished, #1 is resumed
+        // 9: #1 is finished
+        let schedule = RoundRobinScheduler { quantum: 2 }.schedule(interactive());
+
+        assert_eq!(schedule.order(), vec![3, 2, 1]);
+        let segments: Vec<(u64, u32, u32)> = schedule
+            .segments
+            .iter()
+            .map(|s| (s.id, s.start, s.end))
+            .collect();
+        assert_eq!(
+            segments,
+            vec![
+                (1, 0, 2),
+                (2, 2, 4),
+                (3, 4, 5),
+                (1, 5, 7),
+                (2, 7, 8),
+                (1, 8, 9)
+            ]
+        );
+        assert_eq!(
+            schedule.runs[1].projection(),
+            Projection {
+                id: 2,
+                started_at: 2,
+                finished_at: 8,
+            }
+        );
+        assert_eq!(
+            schedule.events[..2],
+            [
+                ScheduleEvent::Preempted {
+                    time: 2,
+                    id: 1,
+                    by: 2,
+                },
+                ScheduleEvent::Preempted {
+                    time: 4,
+                    id: 2,
+                    by: 3,
+                },
+            ]
+        );
+        assert_eq!(schedule.events.len(), 6);
+    }
+
+    #[test]
+    fn large_quantum_is_first_come_first_served() {
+        let schedule = RoundRobinScheduler { quantum: 100 }.schedule(interactive());
+
+        assert_eq!(schedule.order(), vec![1, 2, 3]);
+        assert!(schedule.events.is_empty());
+    }
+
+    #[test]
+    fn lone_task_runs_past_its_quantum_in_one_segment() {
+        let tasks = vec![Task {
+            id: 1,
+            queued_at: 3,
+            execution_duration: 5,
+            ..Default::default()
+        }];
+
+        let schedule = RoundRobinScheduler { quantum: 1 }.schedule(tasks);
+
+        assert_eq!(
+            schedule.segments,
+            vec![Segment {
+                id: 1,
+                core: 0,
+                start: 3,
+                end: 8,
+            }]
+        );
+        assert!(schedule.events.is_empty());
+    }
+
+    #[test]
+    #[should_panic(expected = "quantum must be positive")]
+    fn zero_quantum() {
+        RoundRobinScheduler { quantum: 0 }.schedule(vec![]);
+    }
+}
@@ -0,0 +1,238 @@
+// Shortest remaining time first: SJF that preempts the running task for shorter arrivals.
+use super::{
+    Job, PolicyCapabilities, Schedule, ScheduleEvent, ScheduledTask, Scheduler, Segment, Semantics,
+    Tie,
+};
+use crate::Task;
+use std::collections::BTreeMap;
+
+/// Preemptive shortest remaining time first on a single CPU. Whenever a task arrives with less
+/// work left than the running task, the running task is preempted and put back in the queue with
+/// its remaining time. Equal remaining time never preempts; ties in the queue are broken
+/// according to `semantics`.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct SrtfScheduler {
+    pub semantics: Semantics,
+}
+
+impl SrtfScheduler {
+    fn key(&self, job: &Job) -> (u32, Tie, u64) {
+        (
+            job.remaining,
+            self.semantics.tie_break(&job.task, job.seq),
+            job.seq,
+        )
+    }
+}
+
+impl Scheduler for SrtfScheduler {
+    fn schedule(&self, tasks: Vec<Task>) -> Schedule {
+        let mut pending = Job::pending(tasks);
+        let mut schedule = Schedule::default();
+        let mut time = 0_u32;
+        let mut ready: BTreeMap<(u32, Tie, u64), Job> = BTreeMap::new();
+        // the running job, and when its current segment started
+        let mut running: Option<(Job, u32)> = None;
+
+        loop {
+            while pending
+                .last()
+                .is_some_and(|job| job.task.ready_at() <= time)
+            {
+                let job = pending.pop().unwrap();
+                ready.insert(self.key(&job), job);
+            }
+
+            // a newly queued job with less work left takes over the CPU
+            if let Some((job, start)) = running.take() {
+                match ready.keys().next() {
+                    Some(&(remaining, _, _)) if remaining < job.remaining => {
+                        let by = ready.values().next().unwrap().task.id;
+                        schedule.segments.push(Segment {
+                            id: job.task.id,
+                            core: 0,
+                            start,
+                            end: time,
+                        });
+                        schedule.events.push(ScheduleEvent::Preempted {
+                            time,
+                            id: job.task.id,
+                            by,
+                        });
+                        ready.insert(self.key(&job), job);
+                    }
+                    _ => running = Some((job, start)),
+                }
+            }
+
+            if running.is_none() {
+                let key = ready.keys().next().cloned();
+                match key.and_then(|key| ready.remove(&key)) {
+                    Some(mut job) => {
+                        match job.first_started {
+                            Some(_) => schedule.events.push(ScheduleEvent::Resumed {
+                                time,
+                                id: job.task.id,
+                            }),
+                            None => job.first_started = Some(time),
+                        }
+                        running = Some((job, time));
+                    }
+                    // idle until the next arrival, if there is one
+                    None => match pending.last() {
+                        Some(job) => {
+                            time = job.task.ready_at();
+                            continue;
+                        }
+                        None => break,
+                    },
+                }
+            }
+
+            // run until the job finishes or the next arrival, whichever comes first
+            let (mut job, start) = running.take().unwrap();
+            let finish = time + job.remaining;
+            match pending.last().map(|next| next.task.ready_at()) {
+                Some(arrival) if arrival < finish => {
+                    job.remaining -= arrival - time;
+                    time = arrival;
+                    running = Some((job, start));
+                }
+                _ => {
+                    time = finish;
+                    schedule.segments.push(Segment {
+                        id: job.task.id,
+                        core: 0,
+                        start,
+                        end: time,
+                    });
+                    let started_at = job.first_started.unwrap();
+                    schedule
+                        .runs
+                        .push(ScheduledTask::new(job.task, started_at, time));
+                }
+            }
+        }
+
+        schedule
+    }
+
+    fn capabilities(&self) -> PolicyCapabilities {
+        PolicyCapabilities {
+            preemptive: true,
+            deterministic: true,
+            ..Default::default()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::examples::srtf_textbook;
+    use crate::Projection;
+    use crate::SjfScheduler;
+
+    #[test]
+    fn shorter_arrival_preempts() {
+        // 0: #1 is started
+        // 1: #2 is queued with 4 left against #1's 7, so #1 is preempted
+        // 5: #2 is finished, #4 is started (5 left, vs #1's 7 and #3's 9)
+        // 10: #4 is finished, #1 is resumed
+        // 17: #1 is finished, #3 is started
+        // 26: #3 is finished
+        let schedule = SrtfScheduler::default().schedule(srtf_textbook());
+
+        assert_eq!(schedule.order(), vec![2, 4, 1, 3]);
+        assert_eq!(
+            schedule.runs[2].projection(),
+            Projection {
+                id: 1,
+                started_at: 0,
+                finished_at: 17,
+            }
+        );
+        let segments: Vec<(u64, u32, u32)> = schedule
+            .segments
+            .iter()
+            .map(|s| (s.id, s.start, s.end))
+            .collect();
+        assert_eq!(
+            segments,
+            vec![(1, 0, 1), (2, 1, 5), (4, 5, 10), (1, 10, 17), (3, 17, 26)]
+        );
+        assert_eq!(
+            schedule.events,
+            vec![
+                ScheduleEvent::Preempted {
+                    time: 1,
+                    id: 1,
+                    by: 2,
+                },
+                ScheduleEvent::Resumed { time: 10, id: 1 },
+            ]
+        );
+    }
+
+    #[test]
+    fn compared_with_non_preemptive_sjf() {
+        let sjf = SjfScheduler::default().schedule(srtf_textbook());
+        let srtf = SrtfScheduler::default().schedule(srtf_textbook());
+
+        assert_eq!(sjf.order(), vec![1, 2, 4, 3]);
+        assert!(sjf.events.is_empty());
+        assert_eq!(srtf.order(), vec![2, 4, 1, 3]);
+        // both finish all the work at the same time
+        assert_eq!(sjf.runs.last().unwrap().finished_at, 26);
+        assert_eq!(srtf.runs.last().unwrap().finished_at, 26);
+    }
+
+    #[test]
+    fn equal_remaining_time_does_not_preempt() {
+        let tasks = vec![
+            Task {
+                id: 2,
+                queued_at: 0,
+                execution_duration: 4,
+                ..Default::default()
+            },
+            Task {
+                id: 1,
+                queued_at: 1,
+                execution_duration: 3,
+                ..Default::default()
+            },
+        ];
+
+        let schedule = SrtfScheduler::default().schedule(tasks);
+
+        assert_eq!(schedule.order(), vec![2, 1]);
+        assert!(schedule.events.is_empty());
+    }
+
+    #[test]
+    fn idles_between_arrivals() {
+        let tasks = vec![
+            Task {
+                id: 1,
+                queued_at: 0,
+                execution_duration: 1,
+                ..Default::default()
+            },
+            Task {
+                id: 2,
+                queued_at: 5,
+                execution_duration: 1,
+                ..Default::default()
+            },
+        ];
+
+        let schedule = SrtfScheduler::default().schedule(tasks);
+
+        assert_eq!(schedule.runs[1].started_at, 5);
+        assert_eq!(
+            SrtfScheduler::default().schedule(vec![]),
+            Schedule::default()
+        );
+    }
+}
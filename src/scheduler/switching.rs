@@ -0,0 +1,318 @@
+// Changing policy partway through a run, e.g. to evaluate a migration before rolling it out.
+use super::{PolicyCapabilities, Schedule, ScheduleEvent, ScheduledTask, Scheduler, Segment};
+use crate::Task;
+use std::collections::{BTreeMap, BTreeSet};
+
+/// Runs one single-CPU policy until the first switch time, then the next, and so on. At each
+/// switch, whatever hasn't run yet is handed to the next policy with its remaining work, so the
+/// new policy sees the same ready queue the old one left behind. A task that a non-preemptive
+/// policy has already started finishes before the next policy takes over; one that a
+/// preemptive policy is running is preempted at the switch. Task ids must be unique.
+pub struct SwitchingScheduler {
+    phases: Vec<(u32, Box<dyn Scheduler>)>,
+}
+
+impl SwitchingScheduler {
+    pub fn new(initial: Box<dyn Scheduler>) -> Self {
+        SwitchingScheduler {
+            phases: vec![(0, initial)],
+        }
+    }
+
+    /// Switches to `policy` at `time`. Panics unless switches are added in time order.
+    pub fn switch_at(mut self, time: u32, policy: Box<dyn Scheduler>) -> Self {
+        assert!(
+            self.phases.last().is_some_and(|&(last, _)| last < time),
+            "policy switches must be in increasing time order"
+        );
+        self.phases.push((time, policy));
+        self
+    }
+}
+
+impl Scheduler for SwitchingScheduler {
+    fn schedule(&self, tasks: Vec<Task>) -> Schedule {
+        let mut originals = BTreeMap::new();
+        for task in &tasks {
+            assert!(
+                originals.insert(task.id, task.clone()).is_none(),
+                "task {} appears more than once",
+                task.id
+            );
+        }
+
+        let mut remaining = tasks;
+        let mut segments: Vec<Segment> = vec![];
+        for (i, (_, policy)) in self.phases.iter().enumerate() {
+            let switch = self.phases.get(i + 1).map_or(u32::MAX, |&(at, _)| at);
+            let preemptive = policy.capabilities().preemptive;
+            let schedule = policy.schedule(remaining);
+
+            // the CPU is free for the next policy at the switch, or once a started
+            // non-preemptive task finishes
+            let mut cpu_free = switch;
+            let mut ran: BTreeMap<u64, u32> = BTreeMap::new();
+            for segment in schedule.segments.iter().filter(|s| s.start < switch) {
+                let end = if preemptive {
+                    segment.end.min(switch)
+                } else {
+                    cpu_free = cpu_free.max(segment.end);
+                    segment.end
+                };
+                *ran.entry(segment.id).or_insert(0) += end - segment.start;
+                segments.push(Segment { end, ..*segment });
+            }
+
+            remaining = schedule
+                .runs
+                .into_iter()
+                .filter_map(|run| {
+                    let task = run.task;
+                    // only tasks that ran to the end are done; one that never got the CPU is
+                    // handed on, even if it has no work to do
+                    let left = match ran.get(&task.id) {
+                        Some(&ran) if ran == task.execution_duration => return None,
+                        Some(&ran) => task.execution_duration - ran,
+                        None => task.execution_duration,
+                    };
+                    Some(Task {
+                        execution_duration: left,
+                        not_before: Some(task.ready_at().max(cpu_free)),
+                        ..task
+                    })
+                })
+                .collect();
+            // dependencies finished by now are met, and unknown to the next policy
+            let handed_on: BTreeSet<u64> = remaining.iter().map(|task| task.id).collect();
+            for task in &mut remaining {
+                task.depends_on.retain(|id| handed_on.contains(id));
+            }
+        }
+
+        stitch(segments, &originals)
+    }
+
+    fn capabilities(&self) -> PolicyCapabilities {
+        // only what every phase supports
+        self.phases
+            .iter()
+            .map(|(_, policy)| policy.capabilities())
+            .reduce(PolicyCapabilities::intersect)
+            .unwrap()
+    }
+}
+
+/// Builds a single-CPU schedule from the pieces of time each task ran for, joining a task's
+/// pieces that carry straight on from one another (say, across a policy switch).
+pub(super) fn stitch(mut pieces: Vec<Segment>, originals: &BTreeMap<u64, Task>) -> Schedule {
+    pieces.sort_by_key(|segment| segment.start);
+    let mut segments: Vec<Segment> = vec![];
+    for segment in pieces {
+        match segments.last_mut() {
+            Some(last) if last.id == segment.id && last.end == segment.start => {
+                last.end = segment.end
+            }
+            _ => segments.push(segment),
+        }
+    }
+
+    let mut spans: BTreeMap<u64, (u32, u32)> = BTreeMap::new();
+    let mut events = vec![];
+    for (i, segment) in segments.iter().enumerate() {
+        match spans.get_mut(&segment.id) {
+            Some(span) => {
+                span.1 = segment.end;
+                events.push(ScheduleEvent::Resumed {
+                    time: segment.start,
+                    id: segment.id,
+                });
+            }
+            None => {
+                spans.insert(segment.id, (segment.start, segment.end));
+            }
+        }
+        // a task that comes back later was preempted by whichever runs next
+        let comes_back = segments[i + 1..].iter().any(|s| s.id == segment.id);
+        if let (true, Some(next)) = (comes_back, segments.get(i + 1)) {
+            events.push(ScheduleEvent::Preempted {
+                time: segment.end,
+                id: segment.id,
+                by: next.id,
+            });
+        }
+    }
+
+    let mut runs: Vec<ScheduledTask> = spans
+        .into_iter()
+        .map(|(id, (started_at, finished_at))| {
+            ScheduledTask::new(originals[&id].clone(), started_at, finished_at)
+        })
+        .collect();
+    runs.sort_by_key(|run| run.finished_at);
+    events.sort_by_key(|event| match *event {
+        ScheduleEvent::Preempted { time, .. } => (time, 0),
+        ScheduleEvent::Resumed { time, .. } => (time, 1),
+    });
+
+    Schedule {
+        runs,
+        segments,
+        events,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{PriorityScheduler, SjfScheduler, SrtfScheduler};
+
+    fn task(id: u64, queued_at: u32, execution_duration: u32, priority: Option<u8>) -> Task {
+        Task {
+            id,
+            queued_at,
+            execution_duration,
+            priority,
+            ..Default::default()
+        }
+    }
+
+    fn segments(schedule: &Schedule) -> Vec<(u64, u32, u32)> {
+        schedule
+            .segments
+            .iter()
+            .map(|s| (s.id, s.start, s.end))
+            .collect()
+    }
+
+    fn tasks() -> Vec<Task> {
+        vec![
+            task(1, 0, 4, None),
+            task(2, 0, 2, None),
+            task(3, 1, 1, None),
+            task(4, 1, 6, Some(9)),
+        ]
+    }
+
+    #[test]
+    fn next_policy_takes_over_the_ready_queue() {
+        // 0: #2 is started under SJF
+        // 2: #2 is finished, #3 is started
+        // 3: #3 is finished and priority takes over with #1 and #4 still queued
+        // 9: #4 is finished, #1 is started
+        let policy = SwitchingScheduler::new(Box::new(SjfScheduler::default()))
+            .switch_at(3, Box::new(PriorityScheduler::default()));
+
+        let schedule = policy.schedule(tasks());
+
+        assert_eq!(
+            segments(&schedule),
+            vec![(2, 0, 2), (3, 2, 3), (4, 3, 9), (1, 9, 13)]
+        );
+        assert_eq!(schedule.order(), vec![2, 3, 4, 1]);
+        // waits are measured from when the task was originally ready
+        assert_eq!(schedule.runs[3].wait_time, 9);
+        assert!(schedule.events.is_empty());
+    }
+
+    #[test]
+    fn started_task_finishes_before_the_switch_takes_effect() {
+        let policy = SwitchingScheduler::new(Box::new(SjfScheduler::default()))
+            .switch_at(1, Box::new(PriorityScheduler::default()));
+
+        let schedule = policy.schedule(tasks());
+
+        assert_eq!(
+            segments(&schedule),
+            vec![(2, 0, 2), (4, 2, 8), (3, 8, 9), (1, 9, 13)]
+        );
+    }
+
+    #[test]
+    fn preemptive_policy_hands_over_the_running_task_mid_flight() {
+        // SRTF starts #2 at 1 over #1; switching to priority at 3 preempts #2 for #1
+        let tasks = vec![task(1, 0, 8, Some(5)), task(2, 1, 4, None)];
+        let policy = SwitchingScheduler::new(Box::new(SrtfScheduler::default()))
+            .switch_at(3, Box::new(PriorityScheduler::default()));
+
+        let schedule = policy.schedule(tasks);
+
+        assert_eq!(
+            segments(&schedule),
+            vec![(1, 0, 1), (2, 1, 3), (1, 3, 10), (2, 10, 12)]
+        );
+        assert_eq!(
+            schedule.events,
+            vec![
+                ScheduleEvent::Preempted {
+                    time: 1,
+                    id: 1,
+                    by: 2,
+                },
+                ScheduleEvent::Preempted {
+                    time: 3,
+                    id: 2,
+                    by: 1,
+                },
+                ScheduleEvent::Resumed { time: 3, id: 1 },
+                ScheduleEvent::Resumed { time: 10, id: 2 },
+            ]
+        );
+        assert_eq!(schedule.runs[1].started_at, 1);
+    }
+
+    #[test]
+    fn zero_length_task_queued_at_the_switch_still_runs() {
+        // #2 is queued at 1 behind #1, so is handed to the second policy untouched
+        let tasks = vec![task(1, 0, 5, None), task(2, 1, 0, None)];
+        let policy = SwitchingScheduler::new(Box::new(SjfScheduler::default()))
+            .switch_at(2, Box::new(SjfScheduler::default()));
+
+        let schedule = policy.schedule(tasks.clone());
+
+        assert_eq!(schedule.order(), vec![1, 2]);
+        assert_eq!(segments(&schedule), vec![(1, 0, 5), (2, 5, 5)]);
+    }
+
+    #[test]
+    fn dependencies_met_before_the_switch_are_dropped() {
+        // #1 finishes under the first policy, #2 and #3 wait for it and #3 for #2 as well
+        let mut tasks = vec![
+            task(1, 0, 2, None),
+            task(2, 0, 3, None),
+            task(3, 0, 1, None),
+        ];
+        tasks[1].depends_on = vec![1];
+        tasks[2].depends_on = vec![1, 2];
+        let policy = SwitchingScheduler::new(Box::new(SjfScheduler::default()))
+            .switch_at(1, Box::new(SjfScheduler::default()));
+
+        let schedule = policy.schedule(tasks);
+
+        assert_eq!(segments(&schedule), vec![(1, 0, 2), (2, 2, 5), (3, 5, 6)]);
+        assert_eq!(schedule.runs[2].task.depends_on, vec![1, 2]);
+    }
+
+    #[test]
+    fn unswitched_run_matches_its_only_policy() {
+        let policy = SwitchingScheduler::new(Box::new(SrtfScheduler::default()));
+
+        let tasks = vec![
+            task(1, 0, 8, None),
+            task(2, 1, 4, None),
+            task(3, 2, 9, None),
+        ];
+
+        assert_eq!(
+            policy.schedule(tasks.clone()),
+            SrtfScheduler::default().schedule(tasks)
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "policy switches must be in increasing time order")]
+    fn switches_out_of_order() {
+        SwitchingScheduler::new(Box::new(SjfScheduler::default()))
+            .switch_at(5, Box::new(SjfScheduler::default()))
+            .switch_at(5, Box::new(SjfScheduler::default()));
+    }
+}
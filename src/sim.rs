@@ -0,0 +1,1846 @@
+// Simulation of the single-CPU shortest-job-first queue, projecting when each task will run.
+// Beyond plain prediction this covers start windows, reservations, spawned follow-up work, load
+// shedding, interrupts, impatient tasks and decision tracing.
+use crate::dag::{DependencyError, Dependents};
+use crate::rng::SplitMix64;
+use crate::scheduler::{Semantics, Tie};
+use crate::{Schedule, Task};
+use std::cmp::{Ordering, Reverse};
+use std::collections::{BTreeMap, BinaryHeap, VecDeque};
+use std::fmt;
+
+/// When a task is expected to run, as projected by simulating the queue forward.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Projection {
+    pub id: u64,
+    pub started_at: u32,
+    pub finished_at: u32,
+}
+
+/// Bounds the ready queue so overload sheds work rather than letting the queue grow forever.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Shedding {
+    /// Most tasks allowed to wait in the ready queue at once; arrivals beyond this shed a task.
+    pub max_queued: usize,
+    pub strategy: ShedStrategy,
+}
+
+/// Which task is dropped when an arrival would overflow the ready queue.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ShedStrategy {
+    /// Reject the arriving task.
+    DropNewest,
+    /// Drop the queued (or arriving) task with the longest execution duration.
+    DropLongest,
+    /// Drop a task chosen uniformly at random from the queue and the arrival, seeded so runs
+    /// are reproducible.
+    Random { seed: u64 },
+}
+
+/// Result of `predict_with_shedding`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SheddingOutcome {
+    /// Tasks that ran, in execution order.
+    pub projections: Vec<Projection>,
+    /// Ids of tasks dropped at enqueue time, in the order they were shed.
+    pub shed: Vec<u64>,
+}
+
+/// Like `predict_finish_times`, but caps the ready queue according to `shedding` and reports
+/// which tasks were dropped to keep it there. Reserved tasks are never shed.
+pub fn predict_with_shedding(tasks: Vec<Task>, shedding: Shedding) -> SheddingOutcome {
+    let config = Config {
+        shedding: Some(shedding),
+        ..Default::default()
+    };
+    let outcome = simulate(tasks, |_, _| vec![], config);
+    SheddingOutcome {
+        projections: outcome.projections,
+        shed: outcome.shed,
+    }
+}
+
+/// A task that gave up waiting (see `Task::give_up_after`) before it could start.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Expired {
+    pub id: u64,
+    /// When the task gave up.
+    pub at: u32,
+}
+
+/// Result of `predict_abandonment`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AbandonmentReport {
+    /// Tasks that ran, in execution order.
+    pub projections: Vec<Projection>,
+    /// Tasks that gave up, in the order they did so.
+    pub expired: Vec<Expired>,
+}
+
+impl AbandonmentReport {
+    /// Fraction of all tasks that gave up rather than ran.
+    pub fn abandonment_rate(&self) -> f64 {
+        let total = self.projections.len() + self.expired.len();
+        if total == 0 {
+            0.0
+        } else {
+            self.expired.len() as f64 / total as f64
+        }
+    }
+}
+
+/// Like `predict_finish_times`, but also reports the tasks that gave up waiting. Tasks wait for
+/// their `depends_on` as in `predict_with_dependencies`, and give up along with anything they
+/// depend on. Breaks ties between equal-length tasks according to `semantics`.
+///
+/// Panics if a dependency is unknown or the dependencies form a cycle.
+pub fn predict_abandonment(tasks: Vec<Task>, semantics: Semantics) -> AbandonmentReport {
+    let config = Config {
+        semantics,
+        ..Default::default()
+    };
+    let outcome = Simulation::with_dependencies(tasks, |_, _| vec![], config)
+        .unwrap_or_else(|err| panic!("{}", err))
+        .finish();
+    AbandonmentReport {
+        projections: outcome.projections,
+        expired: outcome.expired,
+    }
+}
+
+/// A ready task considered at a dispatch, with the key values the queue ranks it by: the
+/// shortest `execution_duration` wins, then the lowest `tie_break` (its meaning depends on the
+/// `Semantics` in use; under `Semantics::Custom` it is always 0, as the comparator decides),
+/// then the lowest submission `sequence`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Candidate {
+    pub id: u64,
+    pub execution_duration: u32,
+    pub tie_break: u64,
+    pub sequence: u64,
+}
+
+/// One dispatch: which task was started and what it was chosen from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Decision {
+    pub time: u32,
+    pub chosen: u64,
+    /// Whether the chosen task ran because its reservation came up, rather than by winning the
+    /// queue.
+    pub reserved: bool,
+    /// Every task in the ready queue at the time, best first. A task is skipped over if it
+    /// wouldn't finish before an upcoming reservation.
+    pub candidates: Vec<Candidate>,
+}
+
+impl fmt::Display for Decision {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "t={} start #{}", self.time, self.chosen)?;
+        if self.reserved {
+            write!(f, " (reserved)")?;
+        }
+        write!(f, " from [")?;
+        for (i, c) in self.candidates.iter().enumerate() {
+            if i > 0 {
+                write!(f, ", ")?;
+            }
+            write!(
+                f,
+                "#{} ({}, {}, {})",
+                c.id, c.execution_duration, c.tie_break, c.sequence
+            )?;
+        }
+        write!(f, "]")
+    }
+}
+
+/// Result of `predict_with_trace`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TracedRun {
+    pub projections: Vec<Projection>,
+    /// One entry per dispatch, in time order.
+    pub decisions: Vec<Decision>,
+}
+
+/// Like `predict_finish_times`, but also records each dispatch decision along with the full
+/// set of candidates it was made from, for auditing why the schedule came out the way it did.
+pub fn predict_with_trace(tasks: Vec<Task>, semantics: Semantics) -> TracedRun {
+    let config = Config {
+        semantics,
+        trace: true,
+        ..Default::default()
+    };
+    let outcome = simulate(tasks, |_, _| vec![], config);
+    TracedRun {
+        projections: outcome.projections,
+        decisions: outcome.decisions,
+    }
+}
+
+/// Returns the ids of tasks (in execution order) that could not start before their `start_by`.
+pub fn missed_start_by(tasks: Vec<Task>) -> Vec<u64> {
+    let start_by: BTreeMap<u64, u32> = tasks
+        .iter()
+        .filter_map(|task| task.start_by.map(|t| (task.id, t)))
+        .collect();
+
+    predict_finish_times(tasks)
+        .into_iter()
+        .filter(|p| start_by.get(&p.id).is_some_and(|&t| p.started_at > t))
+        .map(|p| p.id)
+        .collect()
+}
+
+/// Simulates the given tasks forward and returns the projected start/finish of each one, in
+/// execution order. Call it again with the updated task list whenever new tasks arrive to get
+/// refreshed estimates.
+pub fn predict_finish_times(tasks: Vec<Task>) -> Vec<Projection> {
+    predict_finish_times_with_spawner(tasks, |_, _| vec![])
+}
+
+/// Like `predict_finish_times`, but calls `spawn` with each task and its finish time as it
+/// completes. Any tasks returned are queued alongside the rest, so follow-up work can be
+/// generated as the simulation runs rather than expanded up front. Tasks spawned with a
+/// `queued_at` in the past are ready immediately.
+///
+/// The simulation runs until no tasks remain, so `spawn` must eventually stop returning work.
+pub fn predict_finish_times_with_spawner<F>(tasks: Vec<Task>, spawn: F) -> Vec<Projection>
+where
+    F: FnMut(&Task, u32) -> Vec<Task>,
+{
+    simulate(tasks, spawn, Config::default()).projections
+}
+
+/// Like `predict_finish_times`, but each task waits for everything in its `depends_on` to finish
+/// before it becomes eligible. Fails if a dependency is unknown or the dependencies form a cycle.
+pub fn predict_with_dependencies(tasks: Vec<Task>) -> Result<Vec<Projection>, DependencyError> {
+    Ok(
+        Simulation::with_dependencies(tasks, |_, _| vec![], Config::default())?
+            .finish()
+            .projections,
+    )
+}
+
+/// A hardware interrupt that takes over the CPU at `at` for `handling_time` seconds, preempting
+/// whatever task is running. Interrupts arriving while another is being handled run after it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Interrupt {
+    pub at: u32,
+    pub handling_time: u32,
+}
+
+impl Interrupt {
+    /// Interrupts arriving every `period` seconds from `first`, up to (but not including) `until`.
+    pub fn periodic(first: u32, period: u32, handling_time: u32, until: u32) -> Vec<Interrupt> {
+        (first..until)
+            .step_by(period.max(1) as usize)
+            .map(|at| Interrupt { at, handling_time })
+            .collect()
+    }
+}
+
+/// Like `predict_finish_times`, but with the CPU stolen by `interrupts`. A task interrupted while
+/// running resumes once the handler finishes, so its `finished_at` is pushed back by the
+/// handling time; comparing against `predict_finish_times` shows the inflation interrupt load
+/// causes.
+pub fn predict_with_interrupts(tasks: Vec<Task>, interrupts: &[Interrupt]) -> Vec<Projection> {
+    let mut interrupts = interrupts.to_vec();
+    interrupts.sort_by_key(|interrupt| interrupt.at);
+
+    // serialize overlapping handlers into the intervals during which the CPU is unavailable
+    let mut handling: Vec<(u32, u32)> = vec![];
+    for interrupt in interrupts {
+        let start = handling
+            .last()
+            .map_or(interrupt.at, |&(_, end)| end.max(interrupt.at));
+        handling.push((start, start + interrupt.handling_time));
+    }
+
+    let config = Config {
+        handling,
+        ..Default::default()
+    };
+    simulate(tasks, |_, _| vec![], config).projections
+}
+
+/// Slots kept warm after a tagged task finishes, so a later task with the same tag skips its
+/// `cold_start`. When the pool is full the least recently used slot is evicted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WarmPool {
+    /// Most slots kept warm at once; 0 makes every start cold.
+    pub size: usize,
+    /// How long an idle slot stays warm after its task finishes; `None` keeps it until evicted.
+    pub keep_alive: Option<u32>,
+}
+
+/// Result of `predict_with_warm_pool`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WarmPoolReport {
+    /// Tasks in execution order; each run includes any cold start time.
+    pub projections: Vec<Projection>,
+    /// Ids of tasks that started cold, in execution order.
+    pub cold_starts: Vec<u64>,
+    /// Total time spent on cold starts.
+    pub cold_start_time: u32,
+}
+
+/// Like `predict_finish_times`, but each task pays its `cold_start` before running unless the
+/// warm `pool` holds a slot for its tag.
+pub fn predict_with_warm_pool(tasks: Vec<Task>, pool: WarmPool) -> WarmPoolReport {
+    let config = Config {
+        warm_pool: Some(pool),
+        ..Default::default()
+    };
+    let outcome = simulate(tasks, |_, _| vec![], config);
+    WarmPoolReport {
+        projections: outcome.projections,
+        cold_starts: outcome.cold_starts.iter().map(|&(id, _)| id).collect(),
+        cold_start_time: outcome.cold_starts.iter().map(|&(_, cost)| cost).sum(),
+    }
+}
+
+/// Knobs for `simulate` beyond the tasks themselves.
+#[derive(Default)]
+pub(crate) struct Config {
+    pub(crate) semantics: Semantics,
+    pub(crate) shedding: Option<Shedding>,
+    pub(crate) warm_pool: Option<WarmPool>,
+    /// Sorted, non-overlapping `(start, end)` intervals during which interrupt handlers hold the
+    /// CPU.
+    pub(crate) handling: Vec<(u32, u32)>,
+    /// Whether to record a `Decision` for every dispatch.
+    pub(crate) trace: bool,
+}
+
+impl Config {
+    /// The end of the interrupt handler running at `time`, if any.
+    fn handler_end(&self, time: u32) -> Option<u32> {
+        let index = self.handling.partition_point(|&(_, end)| end <= time);
+        self.handling
+            .get(index)
+            .filter(|&&(start, _)| start <= time)
+            .map(|&(_, end)| end)
+    }
+
+    /// When a task started at `start` finishes, accounting for interrupt handlers that preempt it.
+    fn finish_time(&self, start: u32, duration: u32) -> u32 {
+        let mut finish = start + duration;
+        let index = self.handling.partition_point(|&(_, end)| end <= start);
+        for &(handler_start, handler_end) in &self.handling[index..] {
+            if handler_start >= finish {
+                break;
+            }
+            finish += handler_end - handler_start.max(start);
+        }
+        finish
+    }
+}
+
+/// The tags of warm slots, least recently used first, with when each was last released.
+struct WarmSlots {
+    pool: WarmPool,
+    slots: Vec<(u64, u32)>,
+}
+
+impl WarmSlots {
+    fn is_warm(&self, tag: u64, time: u32) -> bool {
+        self.slots.iter().any(|&(t, released)| {
+            t == tag
+                && self
+                    .pool
+                    .keep_alive
+                    .is_none_or(|keep_alive| time <= released.saturating_add(keep_alive))
+        })
+    }
+
+    /// The startup cost `task` would pay if started at `time`.
+    fn startup_cost(&self, task: &Task, time: u32) -> u32 {
+        match task.tag {
+            Some(tag) if self.is_warm(tag, time) => 0,
+            _ => task.cold_start,
+        }
+    }
+
+    /// Leaves a warm slot for `task`'s tag once it finishes, evicting the least recently used
+    /// slot if the pool is full.
+    fn release(&mut self, task: &Task, finished_at: u32) {
+        let tag = match task.tag {
+            Some(tag) if self.pool.size > 0 => tag,
+            _ => return,
+        };
+        self.slots.retain(|&(t, _)| t != tag);
+        if self.slots.len() == self.pool.size {
+            self.slots.remove(0);
+        }
+        self.slots.push((tag, finished_at));
+    }
+}
+
+/// Orders queued tasks in the simulation: (duration, tie-break, sequence number), lowest first.
+type QueueKey = (u32, Tie, u64);
+
+/// A task waiting to be queued, with its sequence number and when it becomes ready: its
+/// `ready_at`, or later if it had to wait for its dependencies.
+struct Pending {
+    seq: u64,
+    ready_at: u32,
+    task: Task,
+}
+
+impl Pending {
+    fn new(seq: u64, task: Task) -> Self {
+        Pending {
+            seq,
+            ready_at: task.ready_at(),
+            task,
+        }
+    }
+
+    /// The moment a reserved task is due to start, never before it is ready.
+    fn reserved_start(&self) -> Option<u32> {
+        self.task.reserved_at.map(|t| t.max(self.ready_at))
+    }
+}
+
+/// Adds `pending` to the reserved tasks or the others, keeping both sorted by (start, sequence
+/// number).
+fn wait_for_start(tasks: &mut VecDeque<Pending>, reserved: &mut Vec<Pending>, pending: Pending) {
+    if let Some(at) = pending.reserved_start() {
+        let index =
+            reserved.partition_point(|p| (p.reserved_start(), p.seq) < (Some(at), pending.seq));
+        reserved.insert(index, pending);
+    } else {
+        let index =
+            tasks.partition_point(|p| (p.ready_at, p.seq) < (pending.ready_at, pending.seq));
+        tasks.insert(index, pending);
+    }
+}
+
+/// A task in the ready queue, compared by its key alone.
+struct Queued {
+    key: QueueKey,
+    ready_at: u32,
+    task: Task,
+}
+
+impl Queued {
+    /// The last moment the task is still willing to start, if it gives up at all.
+    fn gives_up_at(&self) -> Option<u32> {
+        self.task
+            .give_up_after
+            .map(|t| self.ready_at.saturating_add(t))
+    }
+}
+
+impl PartialEq for Queued {
+    fn eq(&self, other: &Self) -> bool {
+        self.key == other.key
+    }
+}
+
+impl Eq for Queued {}
+
+impl PartialOrd for Queued {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Queued {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.key.cmp(&other.key)
+    }
+}
+
+/// The tasks waiting for the CPU. Pushing and taking the next task are O(log n); everything
+/// else (shedding, abandonment, tracing) walks the whole queue, but only runs when the workload
+/// or config asks for it.
+#[derive(Default)]
+struct ReadyQueue {
+    heap: BinaryHeap<Reverse<Queued>>,
+    /// How many queued tasks have a `give_up_after`.
+    impatient: usize,
+}
+
+impl ReadyQueue {
+    fn len(&self) -> usize {
+        self.heap.len()
+    }
+
+    fn is_empty(&self) -> bool {
+        self.heap.is_empty()
+    }
+
+    fn push(&mut self, key: QueueKey, ready_at: u32, task: Task) {
+        if task.give_up_after.is_some() {
+            self.impatient += 1;
+        }
+        self.heap.push(Reverse(Queued {
+            key,
+            ready_at,
+            task,
+        }));
+    }
+
+    /// The next task to run.
+    fn peek(&self) -> Option<&Queued> {
+        self.heap.peek().map(|Reverse(queued)| queued)
+    }
+
+    fn pop(&mut self) -> Option<Task> {
+        let Reverse(queued) = self.heap.pop()?;
+        self.forget(&queued.task);
+        Some(queued.task)
+    }
+
+    /// Everything queued, in the order it would run.
+    fn sorted(&self) -> Vec<&Queued> {
+        let mut queued: Vec<&Queued> = self.heap.iter().map(|Reverse(queued)| queued).collect();
+        queued.sort_unstable();
+        queued
+    }
+
+    /// Takes out every task that `leave` picks, in queue order.
+    fn remove_where<F: FnMut(&Queued) -> bool>(&mut self, mut leave: F) -> Vec<Queued> {
+        let (mut left, stay): (Vec<Queued>, Vec<Queued>) = std::mem::take(&mut self.heap)
+            .into_iter()
+            .map(|Reverse(queued)| queued)
+            .partition(|queued| leave(queued));
+        self.heap = stay.into_iter().map(Reverse).collect();
+        left.sort_unstable();
+        for queued in &left {
+            self.forget(&queued.task);
+        }
+        left
+    }
+
+    /// Takes out the tasks that have run out of patience by `time`.
+    fn remove_expired(&mut self, time: u32) -> Vec<Expired> {
+        if self.impatient == 0 {
+            return vec![];
+        }
+        self.remove_where(|queued| queued.gives_up_at().is_some_and(|at| at < time))
+            .into_iter()
+            .map(|queued| Expired {
+                id: queued.task.id,
+                at: queued.gives_up_at().unwrap(),
+            })
+            .collect()
+    }
+
+    fn forget(&mut self, task: &Task) {
+        if task.give_up_after.is_some() {
+            self.impatient -= 1;
+        }
+    }
+}
+
+/// Everything the simulation produces; the public entry points pick out what they need.
+pub(crate) struct Outcome {
+    pub(crate) projections: Vec<Projection>,
+    pub(crate) shed: Vec<u64>,
+    /// Tasks that gave up, and those left waiting on them, in the order they did so.
+    pub(crate) expired: Vec<Expired>,
+    pub(crate) decisions: Vec<Decision>,
+    /// `(id, startup cost)` of each task that started cold, in execution order.
+    pub(crate) cold_starts: Vec<(u64, u32)>,
+}
+
+pub(crate) fn simulate<F>(tasks: Vec<Task>, spawn: F, config: Config) -> Outcome
+where
+    F: FnMut(&Task, u32) -> Vec<Task>,
+{
+    Simulation::new(tasks, spawn, config).finish()
+}
+
+/// The simulation paused between two runs, so it can be driven one run at a time.
+pub(crate) struct Simulation<F> {
+    spawn: F,
+    config: Config,
+    time: u32,
+    /// Every task gets a sequence number in the order it was submitted (input order, then
+    /// spawned tasks as they are spawned), so same-instant ordering never depends on how the
+    /// pending lists happen to be sorted. This is the next one to hand out.
+    submitted: u64,
+    /// Tasks not yet queued, by (ready time, sequence number).
+    tasks: VecDeque<Pending>,
+    /// Reserved tasks, held back from the queue and started at their reserved time, by
+    /// (reserved start, sequence number).
+    reserved: Vec<Pending>,
+    /// Tasks waiting for their dependencies, which join `tasks` once those finish.
+    held: Dependents,
+    q: ReadyQueue,
+    warm: Option<WarmSlots>,
+    rng: Option<SplitMix64>,
+    shed: Vec<u64>,
+    expired: Vec<Expired>,
+    decisions: Vec<Decision>,
+    cold_starts: Vec<(u64, u32)>,
+}
+
+impl<F> Simulation<F>
+where
+    F: FnMut(&Task, u32) -> Vec<Task>,
+{
+    /// A simulation of `tasks`, ignoring their `depends_on`.
+    pub(crate) fn new(tasks: Vec<Task>, spawn: F, config: Config) -> Self {
+        let count = tasks.len() as u64;
+        Simulation::start(
+            (0..).zip(tasks).collect(),
+            count,
+            Dependents::default(),
+            spawn,
+            config,
+        )
+    }
+
+    /// A simulation of `tasks` in which each waits for everything in its `depends_on` to
+    /// finish. Fails if a dependency is unknown or the dependencies form a cycle.
+    pub(crate) fn with_dependencies(
+        tasks: Vec<Task>,
+        spawn: F,
+        config: Config,
+    ) -> Result<Self, DependencyError> {
+        let count = tasks.len() as u64;
+        let (ready, held) = Dependents::hold(tasks)?;
+        Ok(Simulation::start(ready, count, held, spawn, config))
+    }
+
+    /// Starts on `ready`, numbered by submission out of `submitted` tasks so far, with the
+    /// rest `held` back.
+    fn start(
+        ready: Vec<(u64, Task)>,
+        submitted: u64,
+        held: Dependents,
+        spawn: F,
+        config: Config,
+    ) -> Self {
+        let warm = config.warm_pool.map(|pool| WarmSlots {
+            pool,
+            slots: vec![],
+        });
+        let rng = match config.shedding {
+            Some(Shedding {
+                strategy: ShedStrategy::Random { seed },
+                ..
+            }) => Some(SplitMix64::stream(seed, "shedding")),
+            _ => None,
+        };
+
+        let (mut reserved, mut tasks): (Vec<_>, Vec<_>) = ready
+            .into_iter()
+            .map(|(seq, task)| Pending::new(seq, task))
+            .partition(|pending| pending.task.reserved_at.is_some());
+        reserved.sort_by_key(|pending| (pending.reserved_start(), pending.seq));
+        tasks.sort_by_key(|pending| (pending.ready_at, pending.seq));
+
+        Simulation {
+            spawn,
+            config,
+            time: 0,
+            submitted,
+            tasks: VecDeque::from(tasks),
+            reserved,
+            held,
+            q: ReadyQueue::default(),
+            warm,
+            rng,
+            shed: vec![],
+            expired: vec![],
+            decisions: vec![],
+            cold_starts: vec![],
+        }
+    }
+
+    /// Runs the simulation to the end.
+    pub(crate) fn finish(mut self) -> Outcome {
+        let mut projections = vec![];
+        while let Some((_, projection)) = self.next_run() {
+            projections.push(projection);
+        }
+        let mut expired = self.expired;
+        expired.extend(self.held.stranded(&expired));
+        // stable, so each task still comes after whatever it gave up with
+        expired.sort_by_key(|expired| expired.at);
+        Outcome {
+            projections,
+            shed: self.shed,
+            expired,
+            decisions: self.decisions,
+            cold_starts: self.cold_starts,
+        }
+    }
+
+    /// Runs until the next task is executed and returns it with when it ran, or `None` once
+    /// there is nothing left to run.
+    pub(crate) fn next_run(&mut self) -> Option<(Task, Projection)> {
+        let config = &self.config;
+        let q = &mut self.q;
+        let tasks = &mut self.tasks;
+        let reserved = &mut self.reserved;
+
+        // while there are still tasks to queue & execute
+        while !tasks.is_empty() || !q.is_empty() || !reserved.is_empty() {
+            // nothing can be dispatched while an interrupt handler holds the CPU
+            while let Some(end) = config.handler_end(self.time) {
+                self.time = end;
+            }
+            let time = self.time;
+
+            // add any tasks ready before/during the current time to the queue for execution
+            let arrived = tasks.partition_point(|pending| pending.ready_at <= time);
+            for Pending {
+                seq,
+                ready_at,
+                task,
+            } in tasks.drain(..arrived)
+            {
+                let key = (
+                    task.execution_duration,
+                    config.semantics.tie_break(&task, seq),
+                    seq,
+                );
+                q.push(key, ready_at, task);
+
+                let shedding = match config.shedding {
+                    Some(shedding) if q.len() > shedding.max_queued => shedding,
+                    _ => continue,
+                };
+                // the queue is full, so something has to go, picked out by its sequence number
+                let victim = match shedding.strategy {
+                    ShedStrategy::DropNewest => seq,
+                    ShedStrategy::DropLongest => q.sorted().last().unwrap().key.2,
+                    ShedStrategy::Random { .. } => {
+                        let index = self.rng.as_mut().unwrap().below(q.len() as u64) as usize;
+                        q.sorted()[index].key.2
+                    }
+                };
+                self.shed.extend(
+                    q.remove_where(|queued| queued.key.2 == victim)
+                        .iter()
+                        .map(|queued| queued.task.id),
+                );
+            }
+
+            // anyone who has waited past their patience leaves the queue
+            self.expired.extend(q.remove_expired(time));
+
+            // snapshot what the policy is choosing between, in the order it ranks them
+            let candidates = if config.trace {
+                q.sorted()
+                    .into_iter()
+                    .map(|queued| {
+                        let (execution_duration, ref tie, sequence) = queued.key;
+                        Candidate {
+                            id: queued.task.id,
+                            execution_duration,
+                            tie_break: tie.rank(),
+                            sequence,
+                        }
+                    })
+                    .collect()
+            } else {
+                vec![]
+            };
+
+            let warm = &mut self.warm;
+            let next_reservation = reserved.first().and_then(Pending::reserved_start);
+            let current_task = match next_reservation {
+                // the reserved slot has arrived
+                Some(at) if at <= time => Some(reserved.remove(0).task),
+                // otherwise, only backfill a queued task if it finishes before the next
+                // reservation
+                _ => match q.peek() {
+                    Some(Queued { key, task, .. })
+                        if next_reservation.is_none_or(|at| {
+                            let startup = warm.as_ref().map_or(0, |w| w.startup_cost(task, time));
+                            config.finish_time(time, startup + key.0) <= at
+                        }) =>
+                    {
+                        q.pop()
+                    }
+                    _ => None,
+                },
+            };
+
+            let current_task = match current_task {
+                Some(current_task) => current_task,
+                // otherwise, nothing can run right now
+                // so update time to the next arrival or reservation b/c computer is currently idle
+                None => {
+                    let next = tasks
+                        .front()
+                        .map(|pending| pending.ready_at)
+                        .into_iter()
+                        .chain(next_reservation)
+                        .min();
+                    // if there is nothing left to wait for, every queued task has given up
+                    if let Some(next) = next {
+                        self.time = next;
+                    }
+                    continue;
+                }
+            };
+
+            // execute the task
+            if config.trace {
+                self.decisions.push(Decision {
+                    time,
+                    chosen: current_task.id,
+                    reserved: next_reservation.is_some_and(|at| at <= time),
+                    candidates,
+                });
+            }
+
+            let startup = match warm.as_ref().map(|w| w.startup_cost(&current_task, time)) {
+                Some(0) | None => 0,
+                Some(cost) => {
+                    self.cold_starts.push((current_task.id, cost));
+                    cost
+                }
+            };
+            self.time = config.finish_time(time, startup + current_task.execution_duration);
+            let projection = Projection {
+                id: current_task.id,
+                started_at: time,
+                finished_at: self.time,
+            };
+            if let Some(warm) = warm.as_mut() {
+                warm.release(&current_task, self.time);
+            }
+
+            // dependents keep the sequence numbers they were submitted with, but only become
+            // ready once this task is done
+            for (seq, task) in self.held.release(current_task.id) {
+                let mut pending = Pending::new(seq, task);
+                pending.ready_at = pending.ready_at.max(self.time);
+                wait_for_start(tasks, reserved, pending);
+            }
+            for task in (self.spawn)(&current_task, self.time) {
+                wait_for_start(tasks, reserved, Pending::new(self.submitted, task));
+                self.submitted += 1;
+            }
+            return Some((current_task, projection));
+        }
+        None
+    }
+}
+
+/// A task reaching a given fraction of completion.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ProgressEvent {
+    pub time: u32,
+    pub id: u64,
+    pub fraction: f64,
+}
+
+/// The stretches of time task `id` held a CPU in `schedule`, in time order.
+fn segments_of(schedule: &Schedule, id: u64) -> Vec<(u32, u32)> {
+    schedule
+        .segments
+        .iter()
+        .filter(|segment| segment.id == id)
+        .map(|segment| (segment.start, segment.end))
+        .collect()
+}
+
+/// Returns how far along task `id` is at `time`, from 0.0 before it starts to 1.0 once finished,
+/// or `None` if it isn't in `schedule`. Progress is the share of its CPU time the task has had,
+/// so it stands still while the task is preempted.
+pub fn progress_at(schedule: &Schedule, time: u32, id: u64) -> Option<f64> {
+    let run = schedule.runs.iter().find(|run| run.task.id == id)?;
+    let segments = segments_of(schedule, id);
+    let total: u32 = segments.iter().map(|(start, end)| end - start).sum();
+    let done: u32 = segments
+        .iter()
+        .map(|&(start, end)| time.clamp(start, end) - start)
+        .sum();
+    Some(if time >= run.finished_at {
+        1.0
+    } else if done == 0 {
+        0.0
+    } else {
+        f64::from(done) / f64::from(total)
+    })
+}
+
+/// Emits an event for every task in `schedule` as it passes each of the given `fractions` (e.g.
+/// `0.5` for half-way), ordered by time. Since the clock is in whole seconds, each event is
+/// reported at the first second at which the task has reached that fraction.
+pub fn progress_events(schedule: &Schedule, fractions: &[f64]) -> Vec<ProgressEvent> {
+    let mut events: Vec<ProgressEvent> = schedule
+        .runs
+        .iter()
+        .flat_map(|run| {
+            let id = run.task.id;
+            let segments = segments_of(schedule, id);
+            let total: u32 = segments.iter().map(|(start, end)| end - start).sum();
+            fractions.iter().map(move |&fraction| {
+                // walk the segments until the task has had enough of the CPU
+                let mut needed = (fraction * f64::from(total)).ceil() as u32;
+                let mut time = run.started_at;
+                for &(start, end) in &segments {
+                    time = start + needed.min(end - start);
+                    if needed <= end - start {
+                        break;
+                    }
+                    needed -= end - start;
+                }
+                ProgressEvent { time, id, fraction }
+            })
+        })
+        .collect();
+
+    events.sort_by_key(|event| event.time);
+    events
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{execution_order, execution_schedule, RoundRobinScheduler, Scheduler};
+
+    #[test]
+    fn predicted_finish_times_follow_execution() {
+        // 0: #42 is started
+        // 3: #42 is finished, #44 is started
+        // 5: #44 is finished, #43 is started
+        // 8: #43 is finished
+        let tasks = vec![
+            Task {
+                id: 42,
+                queued_at: 0,
+                execution_duration: 3,
+                ..Default::default()
+            },
+            Task {
+                id: 43,
+                queued_at: 1,
+                execution_duration: 3,
+                ..Default::default()
+            },
+            Task {
+                id: 44,
+                queued_at: 2,
+                execution_duration: 2,
+                ..Default::default()
+            },
+        ];
+
+        assert_eq!(
+            predict_finish_times(tasks),
+            vec![
+                Projection {
+                    id: 42,
+                    started_at: 0,
+                    finished_at: 3,
+                },
+                Projection {
+                    id: 44,
+                    started_at: 3,
+                    finished_at: 5,
+                },
+                Projection {
+                    id: 43,
+                    started_at: 5,
+                    finished_at: 8,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn predicted_start_waits_for_idle_gap() {
+        let tasks = vec![
+            Task {
+                id: 42,
+                queued_at: 0,
+                execution_duration: 1,
+                ..Default::default()
+            },
+            Task {
+                id: 43,
+                queued_at: 3,
+                execution_duration: 3,
+                ..Default::default()
+            },
+        ];
+
+        let projections = predict_finish_times(tasks);
+
+        assert_eq!(projections[1].started_at, 3);
+        assert_eq!(projections[1].finished_at, 6);
+    }
+
+    #[test]
+    fn queued_work_keeps_the_clock_from_skipping_ahead() {
+        // 0: #42 is started
+        // 1: #42 is finished, #43 is started (the clock must not jump to #44's arrival)
+        // 2: #43 is finished, CPU idles
+        // 5: #44 is started
+        let tasks = vec![
+            Task {
+                id: 42,
+                queued_at: 0,
+                execution_duration: 1,
+                ..Default::default()
+            },
+            Task {
+                id: 43,
+                queued_at: 0,
+                execution_duration: 1,
+                ..Default::default()
+            },
+            Task {
+                id: 44,
+                queued_at: 5,
+                execution_duration: 1,
+                ..Default::default()
+            },
+        ];
+
+        assert_eq!(
+            predict_finish_times(tasks),
+            vec![
+                Projection {
+                    id: 42,
+                    started_at: 0,
+                    finished_at: 1,
+                },
+                Projection {
+                    id: 43,
+                    started_at: 1,
+                    finished_at: 2,
+                },
+                Projection {
+                    id: 44,
+                    started_at: 5,
+                    finished_at: 6,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn not_before_defers_start() {
+        // #42 is queued first but may not start until 4, so #43 runs in the meantime
+        let tasks = vec![
+            Task {
+                id: 42,
+                queued_at: 0,
+                execution_duration: 1,
+                not_before: Some(4),
+                ..Default::default()
+            },
+            Task {
+                id: 43,
+                queued_at: 1,
+                execution_duration: 2,
+                ..Default::default()
+            },
+        ];
+
+        let projections = predict_finish_times(tasks);
+
+        assert_eq!(projections[0].id, 43);
+        assert_eq!(projections[1].id, 42);
+        assert_eq!(projections[1].started_at, 4);
+    }
+
+    #[test]
+    fn missed_start_by_flags_late_starts() {
+        // 0: #42 is started
+        // 5: #42 is finished, #43 is started (after its start_by of 3)
+        // 6: #43 is finished, #44 is started (within its start_by of 6)
+        let tasks = vec![
+            Task {
+                id: 42,
+                queued_at: 0,
+                execution_duration: 5,
+                ..Default::default()
+            },
+            Task {
+                id: 43,
+                queued_at: 1,
+                execution_duration: 1,
+                start_by: Some(3),
+                ..Default::default()
+            },
+            Task {
+                id: 44,
+                queued_at: 1,
+                execution_duration: 2,
+                start_by: Some(6),
+                ..Default::default()
+            },
+        ];
+
+        assert_eq!(missed_start_by(tasks), vec![43]);
+    }
+
+    #[test]
+    fn spawned_tasks_are_queued_on_completion() {
+        // 0: #42 is started
+        // 2: #42 is finished and spawns #100 (queued at 2) and #101 (queued at 4)
+        // 2: #100 is started
+        // 3: #100 is finished, #43 is started
+        // 6: #43 is finished, #101 is started
+        // 7: #101 is finished
+        let tasks = vec![
+            Task {
+                id: 42,
+                queued_at: 0,
+                execution_duration: 2,
+                ..Default::default()
+            },
+            Task {
+                id: 43,
+                queued_at: 1,
+                execution_duration: 3,
+                ..Default::default()
+            },
+        ];
+
+        let projections = predict_finish_times_with_spawner(tasks, |task, finished_at| {
+            if task.id != 42 {
+                return vec![];
+            }
+            vec![
+                Task {
+                    id: 100,
+                    queued_at: finished_at,
+                    execution_duration: 1,
+                    ..Default::default()
+                },
+                Task {
+                    id: 101,
+                    queued_at: finished_at + 2,
+                    execution_duration: 1,
+                    ..Default::default()
+                },
+            ]
+        });
+
+        let order: Vec<u64> = projections.iter().map(|p| p.id).collect();
+        assert_eq!(order, vec![42, 100, 43, 101]);
+        assert_eq!(projections[3].started_at, 6);
+    }
+
+    #[test]
+    fn spawner_can_recurse() {
+        // each task spawns a half-length child until the duration reaches 1
+        let tasks = vec![Task {
+            id: 1,
+            queued_at: 0,
+            execution_duration: 8,
+            ..Default::default()
+        }];
+
+        let projections = predict_finish_times_with_spawner(tasks, |task, finished_at| {
+            if task.execution_duration == 1 {
+                return vec![];
+            }
+            vec![Task {
+                id: task.id + 1,
+                queued_at: finished_at,
+                execution_duration: task.execution_duration / 2,
+                ..Default::default()
+            }]
+        });
+
+        let order: Vec<u64> = projections.iter().map(|p| p.id).collect();
+        assert_eq!(order, vec![1, 2, 3, 4]);
+        assert_eq!(projections.last().unwrap().finished_at, 15);
+    }
+
+    #[test]
+    fn reservation_keeps_slot_free() {
+        // #42 has the CPU reserved at 4, so the 5 second #43 can't start at 0
+        // 0: #44 is started (it finishes before the reservation)
+        // 2: #44 is finished, idle until the reservation
+        // 4: #42 is started
+        // 5: #42 is finished, #43 is started
+        let tasks = vec![
+            Task {
+                id: 42,
+                queued_at: 0,
+                execution_duration: 1,
+                reserved_at: Some(4),
+                ..Default::default()
+            },
+            Task {
+                id: 43,
+                queued_at: 0,
+                execution_duration: 5,
+                ..Default::default()
+            },
+            Task {
+                id: 44,
+                queued_at: 0,
+                execution_duration: 2,
+                ..Default::default()
+            },
+        ];
+
+        assert_eq!(
+            predict_finish_times(tasks),
+            vec![
+                Projection {
+                    id: 44,
+                    started_at: 0,
+                    finished_at: 2,
+                },
+                Projection {
+                    id: 42,
+                    started_at: 4,
+                    finished_at: 5,
+                },
+                Projection {
+                    id: 43,
+                    started_at: 5,
+                    finished_at: 10,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn backfill_picks_up_late_arrivals_that_fit() {
+        // 0: #43 is queued but too long to finish before the reservation at 5
+        // 1: #44 is queued and fits, so it is started
+        // 3: #44 is finished
+        // 5: #42 is started
+        let tasks = vec![
+            Task {
+                id: 42,
+                queued_at: 0,
+                execution_duration: 2,
+                reserved_at: Some(5),
+                ..Default::default()
+            },
+            Task {
+                id: 43,
+                queued_at: 0,
+                execution_duration: 6,
+                ..Default::default()
+            },
+            Task {
+                id: 44,
+                queued_at: 1,
+                execution_duration: 2,
+                ..Default::default()
+            },
+        ];
+
+        assert_eq!(execution_order(tasks), vec![44, 42, 43]);
+    }
+
+    #[test]
+    fn progress_of_running_task() {
+        let tasks = vec![
+            Task {
+                id: 42,
+                queued_at: 0,
+                execution_duration: 2,
+                ..Default::default()
+            },
+            Task {
+                id: 43,
+                queued_at: 0,
+                execution_duration: 4,
+                ..Default::default()
+            },
+        ];
+
+        let schedule = execution_schedule(tasks);
+
+        assert_eq!(progress_at(&schedule, 1, 42), Some(0.5));
+        assert_eq!(progress_at(&schedule, 1, 43), Some(0.0));
+        assert_eq!(progress_at(&schedule, 5, 43), Some(0.75));
+        assert_eq!(progress_at(&schedule, 9, 43), Some(1.0));
+        assert_eq!(progress_at(&schedule, 1, 44), None);
+    }
+
+    #[test]
+    fn progress_stands_still_while_preempted() {
+        // 0-1: #42, 1-2: #43, 2-3: #42, 3-4: #43, 4-5: #42
+        let tasks = vec![
+            Task {
+                id: 42,
+                queued_at: 0,
+                execution_duration: 3,
+                ..Default::default()
+            },
+            Task {
+                id: 43,
+                queued_at: 1,
+                execution_duration: 2,
+                ..Default::default()
+            },
+        ];
+
+        let schedule = RoundRobinScheduler { quantum: 1 }.schedule(tasks);
+
+        assert_eq!(progress_at(&schedule, 1, 42), Some(1.0 / 3.0));
+        assert_eq!(progress_at(&schedule, 2, 42), Some(1.0 / 3.0));
+        assert_eq!(progress_at(&schedule, 3, 42), Some(2.0 / 3.0));
+        assert_eq!(progress_at(&schedule, 3, 43), Some(0.5));
+
+        let events = progress_events(&schedule, &[0.5, 1.0]);
+
+        let times: Vec<(u32, u64)> = events.iter().map(|e| (e.time, e.id)).collect();
+        assert_eq!(times, vec![(2, 43), (3, 42), (4, 43), (5, 42)]);
+    }
+
+    #[test]
+    fn progress_events_at_fractions() {
+        // 0: #42 is started
+        // 3: #42 is finished, #43 is started
+        // 5: #43 is finished
+        let tasks = vec![
+            Task {
+                id: 42,
+                queued_at: 0,
+                execution_duration: 3,
+                ..Default::default()
+            },
+            Task {
+                id: 43,
+                queued_at: 1,
+                execution_duration: 2,
+                ..Default::default()
+            },
+        ];
+
+        let events = progress_events(&execution_schedule(tasks), &[0.5, 1.0]);
+
+        let times: Vec<(u32, u64)> = events.iter().map(|e| (e.time, e.id)).collect();
+        assert_eq!(times, vec![(2, 42), (3, 42), (4, 43), (5, 43)]);
+    }
+
+    fn burst() -> Vec<Task> {
+        // #1 starts at 0 and the rest all queue up behind it
+        vec![
+            Task {
+                id: 1,
+                queued_at: 0,
+                execution_duration: 10,
+                ..Default::default()
+            },
+            Task {
+                id: 2,
+                queued_at: 1,
+                execution_duration: 4,
+                ..Default::default()
+            },
+            Task {
+                id: 3,
+                queued_at: 2,
+                execution_duration: 6,
+                ..Default::default()
+            },
+            Task {
+                id: 4,
+                queued_at: 3,
+                execution_duration: 2,
+                ..Default::default()
+            },
+        ]
+    }
+
+    #[test]
+    fn shedding_drop_newest_rejects_arrivals() {
+        let shedding = Shedding {
+            max_queued: 2,
+            strategy: ShedStrategy::DropNewest,
+        };
+
+        let outcome = predict_with_shedding(burst(), shedding);
+
+        let order: Vec<u64> = outcome.projections.iter().map(|p| p.id).collect();
+        assert_eq!(order, vec![1, 2, 3]);
+        assert_eq!(outcome.shed, vec![4]);
+    }
+
+    #[test]
+    fn shedding_drop_longest_keeps_short_work() {
+        let shedding = Shedding {
+            max_queued: 2,
+            strategy: ShedStrategy::DropLongest,
+        };
+
+        let outcome = predict_with_shedding(burst(), shedding);
+
+        let order: Vec<u64> = outcome.projections.iter().map(|p| p.id).collect();
+        assert_eq!(order, vec![1, 4, 2]);
+        assert_eq!(outcome.shed, vec![3]);
+    }
+
+    #[test]
+    fn shedding_random_is_reproducible() {
+        let shedding = Shedding {
+            max_queued: 1,
+            strategy: ShedStrategy::Random { seed: 42 },
+        };
+
+        let first = predict_with_shedding(burst(), shedding);
+        let second = predict_with_shedding(burst(), shedding);
+
+        assert_eq!(first, second);
+        assert_eq!(first.shed.len(), 2);
+        assert_eq!(first.projections.len(), 2);
+    }
+
+    #[test]
+    fn interrupts_inflate_running_task() {
+        // 0: #42 is started
+        // 2: interrupt preempts #42 for 3 seconds
+        // 5: #42 resumes
+        // 6: #42 is finished, #43 is started
+        // 8: #43 is finished
+        let tasks = vec![
+            Task {
+                id: 42,
+                queued_at: 0,
+                execution_duration: 3,
+                ..Default::default()
+            },
+            Task {
+                id: 43,
+                queued_at: 1,
+                execution_duration: 2,
+                ..Default::default()
+            },
+        ];
+        let interrupts = [Interrupt {
+            at: 2,
+            handling_time: 3,
+        }];
+
+        assert_eq!(
+            predict_with_interrupts(tasks, &interrupts),
+            vec![
+                Projection {
+                    id: 42,
+                    started_at: 0,
+                    finished_at: 6,
+                },
+                Projection {
+                    id: 43,
+                    started_at: 6,
+                    finished_at: 8,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn tasks_wait_for_interrupt_handler_to_finish() {
+        // 0: interrupt is handled, #42 is queued
+        // 1: second interrupt arrives and waits for the first handler
+        // 4: #42 is started
+        let tasks = vec![Task {
+            id: 42,
+            queued_at: 0,
+            execution_duration: 2,
+            ..Default::default()
+        }];
+        let interrupts = [
+            Interrupt {
+                at: 0,
+                handling_time: 2,
+            },
+            Interrupt {
+                at: 1,
+                handling_time: 2,
+            },
+        ];
+
+        let projections = predict_with_interrupts(tasks, &interrupts);
+
+        assert_eq!(projections[0].started_at, 4);
+        assert_eq!(projections[0].finished_at, 6);
+    }
+
+    #[test]
+    fn periodic_interrupt_load() {
+        // a 1 second handler every 4 seconds steals a quarter of the CPU
+        let tasks = vec![Task {
+            id: 42,
+            queued_at: 1,
+            execution_duration: 6,
+            ..Default::default()
+        }];
+        let interrupts = Interrupt::periodic(0, 4, 1, 100);
+
+        assert_eq!(interrupts.len(), 25);
+        assert_eq!(interrupts[1].at, 4);
+        // runs 1-4, preempted 4-5, runs 5-8
+        assert_eq!(
+            predict_with_interrupts(tasks, &interrupts)[0].finished_at,
+            8
+        );
+    }
+
+    #[test]
+    fn spawned_tasks_arrive_after_submitted_ones() {
+        // #2 and #3 are queued at 0 behind #1, and #1 spawns #100 with the same duration
+        let tasks = vec![
+            Task {
+                id: 1,
+                queued_at: 0,
+                execution_duration: 1,
+                ..Default::default()
+            },
+            Task {
+                id: 3,
+                queued_at: 0,
+                execution_duration: 2,
+                ..Default::default()
+            },
+            Task {
+                id: 2,
+                queued_at: 0,
+                execution_duration: 2,
+                ..Default::default()
+            },
+        ];
+        let spawn = |task: &Task, finished_at| {
+            if task.id != 1 {
+                return vec![];
+            }
+            vec![Task {
+                id: 0,
+                queued_at: finished_at,
+                execution_duration: 2,
+                ..Default::default()
+            }]
+        };
+        let config = Config {
+            semantics: Semantics::ArrivalOrder,
+            ..Default::default()
+        };
+
+        let order: Vec<u64> = simulate(tasks, spawn, config)
+            .projections
+            .iter()
+            .map(|p| p.id)
+            .collect();
+
+        assert_eq!(order, vec![1, 3, 2, 0]);
+    }
+
+    #[test]
+    fn impatient_tasks_give_up() {
+        // 0: #42 is started
+        // 1: #43 is queued, giving up after 2 seconds
+        // 1: #44 is queued, giving up after 5 seconds
+        // 3: #43 gives up
+        // 4: #42 is finished, #44 is started
+        let tasks = vec![
+            Task {
+                id: 42,
+                queued_at: 0,
+                execution_duration: 4,
+                ..Default::default()
+            },
+            Task {
+                id: 43,
+                queued_at: 1,
+                execution_duration: 1,
+                give_up_after: Some(2),
+                ..Default::default()
+            },
+            Task {
+                id: 44,
+                queued_at: 1,
+                execution_duration: 2,
+                give_up_after: Some(5),
+                ..Default::default()
+            },
+        ];
+
+        let report = predict_abandonment(tasks, Semantics::BTree);
+
+        let order: Vec<u64> = report.projections.iter().map(|p| p.id).collect();
+        assert_eq!(order, vec![42, 44]);
+        assert_eq!(report.expired, vec![Expired { id: 43, at: 3 }]);
+        assert_eq!(report.abandonment_rate(), 1.0 / 3.0);
+    }
+
+    #[test]
+    fn dependents_give_up_with_their_dependency() {
+        // 3: #43 gives up, so #45 (waiting on it) and #46 (waiting on #45) never can run
+        let tasks = vec![
+            Task {
+                id: 42,
+                queued_at: 0,
+                execution_duration: 4,
+                ..Default::default()
+            },
+            Task {
+                id: 43,
+                queued_at: 1,
+                execution_duration: 1,
+                give_up_after: Some(2),
+                ..Default::default()
+            },
+            Task {
+                id: 46,
+                execution_duration: 1,
+                depends_on: vec![45],
+                ..Default::default()
+            },
+            Task {
+                id: 45,
+                execution_duration: 1,
+                depends_on: vec![43],
+                ..Default::default()
+            },
+        ];
+
+        let report = predict_abandonment(tasks, Semantics::BTree);
+
+        assert_eq!(report.projections.len(), 1);
+        assert_eq!(
+            report.expired,
+            vec![
+                Expired { id: 43, at: 3 },
+                Expired { id: 45, at: 3 },
+                Expired { id: 46, at: 3 },
+            ]
+        );
+    }
+
+    #[test]
+    fn task_starting_at_its_limit_still_runs() {
+        let tasks = vec![
+            Task {
+                id: 42,
+                queued_at: 0,
+                execution_duration: 3,
+                ..Default::default()
+            },
+            Task {
+                id: 43,
+                queued_at: 0,
+                execution_duration: 3,
+                give_up_after: Some(3),
+                ..Default::default()
+            },
+            Task {
+                id: 44,
+                queued_at: 0,
+                execution_duration: 3,
+                give_up_after: Some(3),
+                ..Default::default()
+            },
+        ];
+
+        // #43 starts at exactly 3, #44 would have to wait until 6
+        assert_eq!(execution_order(tasks), vec![42, 43]);
+    }
+
+    #[test]
+    fn trace_records_candidates_per_dispatch() {
+        // 0: #42 is the only candidate
+        // 3: #44 beats #43 on duration
+        // 5: #43 is all that's left
+        let tasks = vec![
+            Task {
+                id: 42,
+                queued_at: 0,
+                execution_duration: 3,
+                ..Default::default()
+            },
+            Task {
+                id: 43,
+                queued_at: 1,
+                execution_duration: 3,
+                ..Default::default()
+            },
+            Task {
+                id: 44,
+                queued_at: 2,
+                execution_duration: 2,
+                ..Default::default()
+            },
+        ];
+
+        let run = predict_with_trace(tasks, Semantics::BTree);
+
+        assert_eq!(run.decisions.len(), 3);
+        let second = &run.decisions[1];
+        assert_eq!(second.time, 3);
+        assert_eq!(second.chosen, 44);
+        assert_eq!(
+            second.candidates,
+            vec![
+                Candidate {
+                    id: 44,
+                    execution_duration: 2,
+                    tie_break: 44,
+                    sequence: 2,
+                },
+                Candidate {
+                    id: 43,
+                    execution_duration: 3,
+                    tie_break: 43,
+                    sequence: 1,
+                },
+            ]
+        );
+        assert_eq!(
+            second.to_string(),
+            "t=3 start #44 from [#44 (2, 44, 2), #43 (3, 43, 1)]"
+        );
+    }
+
+    #[test]
+    fn trace_marks_reserved_dispatch() {
+        let tasks = vec![
+            Task {
+                id: 42,
+                queued_at: 0,
+                execution_duration: 1,
+                reserved_at: Some(2),
+                ..Default::default()
+            },
+            Task {
+                id: 43,
+                queued_at: 0,
+                execution_duration: 5,
+                ..Default::default()
+            },
+        ];
+
+        let run = predict_with_trace(tasks, Semantics::BTree);
+
+        assert_eq!(run.decisions[0].chosen, 42);
+        assert!(run.decisions[0].reserved);
+        assert_eq!(run.decisions[0].candidates.len(), 1);
+        assert!(!run.decisions[1].reserved);
+    }
+
+    #[test]
+    fn tracing_does_not_change_the_schedule() {
+        let run = predict_with_trace(burst(), Semantics::BTree);
+
+        assert_eq!(run.projections, predict_finish_times(burst()));
+    }
+
+    fn containers() -> Vec<Task> {
+        [(1, 7, 1, 3), (2, 7, 2, 3), (3, 8, 3, 2), (4, 7, 4, 3)]
+            .iter()
+            .map(|&(id, tag, execution_duration, cold_start)| Task {
+                id,
+                queued_at: 0,
+                execution_duration,
+                tag: Some(tag),
+                cold_start,
+                ..Default::default()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn warm_slot_skips_cold_start() {
+        // 0: #1 is started cold
+        // 4: #1 is finished, #2 is started warm
+        // 6: #2 is finished, #3 is started cold, evicting tag 7 from the only slot
+        // 11: #3 is finished, #4 is started cold
+        // 18: #4 is finished
+        let report = predict_with_warm_pool(
+            containers(),
+            WarmPool {
+                size: 1,
+                keep_alive: None,
+            },
+        );
+
+        assert_eq!(report.cold_starts, vec![1, 3, 4]);
+        assert_eq!(report.cold_start_time, 8);
+        assert_eq!(report.projections[1].finished_at, 6);
+        assert_eq!(report.projections[3].finished_at, 18);
+    }
+
+    #[test]
+    fn larger_pool_keeps_more_tags_warm() {
+        let report = predict_with_warm_pool(
+            containers(),
+            WarmPool {
+                size: 2,
+                keep_alive: None,
+            },
+        );
+
+        assert_eq!(report.cold_starts, vec![1, 3]);
+        assert_eq!(report.cold_start_time, 5);
+        assert_eq!(report.projections[3].finished_at, 15);
+    }
+
+    #[test]
+    fn idle_slot_goes_cold_after_keep_alive() {
+        let tasks = vec![
+            Task {
+                id: 1,
+                queued_at: 0,
+                execution_duration: 1,
+                tag: Some(7),
+                cold_start: 2,
+                ..Default::default()
+            },
+            Task {
+                id: 2,
+                queued_at: 10,
+                execution_duration: 1,
+                tag: Some(7),
+                cold_start: 2,
+                ..Default::default()
+            },
+        ];
+        let pool = |keep_alive| WarmPool {
+            size: 1,
+            keep_alive: Some(keep_alive),
+        };
+
+        // #1 finishes at 3, so its slot is warm until 3 + keep_alive
+        assert_eq!(
+            predict_with_warm_pool(tasks.clone(), pool(1)).cold_starts,
+            vec![1, 2]
+        );
+        assert_eq!(predict_with_warm_pool(tasks, pool(7)).cold_starts, vec![1]);
+    }
+
+    #[test]
+    fn cold_start_delays_backfill_past_reservation() {
+        let tasks = vec![
+            Task {
+                id: 1,
+                queued_at: 0,
+                execution_duration: 2,
+                cold_start: 2,
+                ..Default::default()
+            },
+            Task {
+                id: 2,
+                queued_at: 0,
+                execution_duration: 1,
+                reserved_at: Some(3),
+                ..Default::default()
+            },
+        ];
+
+        // #1 alone fits before the reservation, but not once it has to start cold
+        assert_eq!(execution_order(tasks.clone()), vec![1, 2]);
+        let report = predict_with_warm_pool(
+            tasks,
+            WarmPool {
+                size: 0,
+                keep_alive: None,
+            },
+        );
+        assert_eq!(report.projections[0].id, 2);
+    }
+}
@@ -0,0 +1,201 @@
+// Mergeable quantile sketches, for percentiles over more completions than it's sensible to keep.
+use crate::Schedule;
+use std::collections::BTreeMap;
+
+/// A DDSketch: approximates quantiles of non-negative values to within a fixed relative error,
+/// using memory logarithmic in the range of values rather than linear in their count. Sketches
+/// with the same accuracy can be merged, e.g. to combine Monte Carlo replications.
+#[derive(Debug, Clone, PartialEq)]
+pub struct QuantileSketch {
+    relative_accuracy: f64,
+    ln_gamma: f64,
+    /// Counts of values in `(gamma^(i-1), gamma^i]`, by `i`.
+    buckets: BTreeMap<i32, u64>,
+    zeros: u64,
+    count: u64,
+}
+
+impl QuantileSketch {
+    /// A sketch whose quantiles are within `relative_accuracy` (e.g. 0.01 for 1%) of a true value.
+    /// Panics unless `relative_accuracy` is strictly between 0 and 1.
+    pub fn new(relative_accuracy: f64) -> Self {
+        assert!(
+            relative_accuracy > 0.0 && relative_accuracy < 1.0,
+            "relative accuracy must be between 0 and 1"
+        );
+        let gamma = (1.0 + relative_accuracy) / (1.0 - relative_accuracy);
+        QuantileSketch {
+            relative_accuracy,
+            ln_gamma: gamma.ln(),
+            buckets: BTreeMap::new(),
+            zeros: 0,
+            count: 0,
+        }
+    }
+
+    pub fn add(&mut self, value: u32) {
+        self.count += 1;
+        if value == 0 {
+            self.zeros += 1;
+        } else {
+            let index = (f64::from(value).ln() / self.ln_gamma).ceil() as i32;
+            *self.buckets.entry(index).or_insert(0) += 1;
+        }
+    }
+
+    /// How many values have been added.
+    pub fn count(&self) -> u64 {
+        self.count
+    }
+
+    /// The approximate `q`th quantile (0.0 to 1.0), or `None` if nothing was added.
+    pub fn quantile(&self, q: f64) -> Option<f64> {
+        if self.count == 0 {
+            return None;
+        }
+        let rank = (q.clamp(0.0, 1.0) * (self.count - 1) as f64) as u64;
+        if rank < self.zeros {
+            return Some(0.0);
+        }
+        let mut seen = self.zeros;
+        for (&index, &n) in &self.buckets {
+            seen += n;
+            if rank < seen {
+                // the point within the bucket with equal relative error to either bound
+                let gamma = self.ln_gamma.exp();
+                return Some(2.0 * gamma.powi(index) / (gamma + 1.0));
+            }
+        }
+        unreachable!("rank is below count")
+    }
+
+    /// Adds every value in `other` to this sketch. Panics if the sketches have different
+    /// accuracies, as their buckets wouldn't line up.
+    pub fn merge(&mut self, other: &QuantileSketch) {
+        assert_eq!(
+            self.relative_accuracy, other.relative_accuracy,
+            "cannot merge sketches of different accuracy"
+        );
+        for (&index, &n) in &other.buckets {
+            *self.buckets.entry(index).or_insert(0) += n;
+        }
+        self.zeros += other.zeros;
+        self.count += other.count;
+    }
+}
+
+/// Wait and turnaround sketches accumulated over any number of schedules.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScheduleSketches {
+    pub wait: QuantileSketch,
+    pub turnaround: QuantileSketch,
+}
+
+impl ScheduleSketches {
+    pub fn new(relative_accuracy: f64) -> Self {
+        ScheduleSketches {
+            wait: QuantileSketch::new(relative_accuracy),
+            turnaround: QuantileSketch::new(relative_accuracy),
+        }
+    }
+
+    /// Adds every run in `schedule`; the schedule itself can then be dropped.
+    pub fn observe(&mut self, schedule: &Schedule) {
+        for run in &schedule.runs {
+            self.wait.add(run.wait_time);
+            self.turnaround
+                .add(run.finished_at.saturating_sub(run.task.queued_at));
+        }
+    }
+
+    pub fn merge(&mut self, other: &ScheduleSketches) {
+        self.wait.merge(&other.wait);
+        self.turnaround.merge(&other.turnaround);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stats::nearest_rank;
+    use crate::workload::{fit, WorkloadModel};
+    use crate::{execution_schedule, Task};
+
+    fn within(estimate: f64, exact: u32, accuracy: f64) -> bool {
+        (estimate - f64::from(exact)).abs() <= accuracy * f64::from(exact)
+    }
+
+    #[test]
+    fn quantiles_are_within_relative_accuracy() {
+        let values: Vec<u32> = (0..10_000).map(|i| (i * 7919) % 100_000).collect();
+        let mut sketch = QuantileSketch::new(0.01);
+        for &v in &values {
+            sketch.add(v);
+        }
+        let mut sorted = values.clone();
+        sorted.sort_unstable();
+
+        assert_eq!(sketch.count(), 10_000);
+        for p in [1, 50, 90, 99] {
+            let estimate = sketch.quantile(p as f64 / 100.0).unwrap();
+            let exact = nearest_rank(&sorted, p);
+            assert!(
+                within(estimate, exact, 0.011),
+                "p{}: {} vs {}",
+                p,
+                estimate,
+                exact
+            );
+        }
+        assert_eq!(QuantileSketch::new(0.01).quantile(0.5), None);
+    }
+
+    #[test]
+    fn zeros_are_exact() {
+        let mut sketch = QuantileSketch::new(0.05);
+        for v in [0, 0, 0, 10] {
+            sketch.add(v);
+        }
+
+        assert_eq!(sketch.quantile(0.5), Some(0.0));
+        assert!(within(sketch.quantile(1.0).unwrap(), 10, 0.05));
+    }
+
+    #[test]
+    fn merged_replications_match_one_big_sketch() {
+        let model: WorkloadModel = fit(&[
+            Task {
+                id: 1,
+                queued_at: 0,
+                execution_duration: 1,
+                ..Default::default()
+            },
+            Task {
+                id: 2,
+                queued_at: 4,
+                execution_duration: 5,
+                ..Default::default()
+            },
+        ])
+        .unwrap();
+
+        let mut merged = ScheduleSketches::new(0.01);
+        let mut all = ScheduleSketches::new(0.01);
+        for seed in 0..4 {
+            let schedule = execution_schedule(model.generate(500, seed));
+            let mut replication = ScheduleSketches::new(0.01);
+            replication.observe(&schedule);
+            merged.merge(&replication);
+            all.observe(&schedule);
+        }
+
+        assert_eq!(merged, all);
+        assert_eq!(merged.wait.count(), 2000);
+    }
+
+    #[test]
+    #[should_panic(expected = "cannot merge sketches of different accuracy")]
+    fn accuracies_must_match() {
+        QuantileSketch::new(0.01).merge(&QuantileSketch::new(0.02));
+    }
+}
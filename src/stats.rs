@@ -0,0 +1,230 @@
+// Summary statistics for a run, and scoring of externally produced orderings.
+use crate::{Projection, Schedule, Task};
+use std::collections::{BTreeMap, BTreeSet};
+
+/// Headline numbers for one run of a workload.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ScheduleStats {
+    /// When the last task finished.
+    pub makespan: u32,
+    /// Mean time from when a task became ready to when it started.
+    pub mean_wait: f64,
+    pub max_wait: u32,
+    /// 99th percentile wait (nearest rank).
+    pub p99_wait: u32,
+    /// Mean time from when a task was queued to when it finished.
+    pub mean_turnaround: f64,
+    /// How many tasks started after their `start_by`.
+    pub missed_start_by: usize,
+}
+
+impl ScheduleStats {
+    /// Computes stats for `projections` of `tasks`. Projections for ids not in `tasks` are
+    /// ignored.
+    pub fn from_projections(tasks: &[Task], projections: &[Projection]) -> ScheduleStats {
+        let tasks: BTreeMap<u64, &Task> = tasks.iter().map(|task| (task.id, task)).collect();
+        ScheduleStats::from_runs(projections.iter().filter_map(|p| {
+            tasks
+                .get(&p.id)
+                .map(|&task| (task, p.started_at, p.finished_at))
+        }))
+    }
+
+    /// Computes stats for each task with when it started and finished.
+    fn from_runs<'a, I>(runs: I) -> ScheduleStats
+    where
+        I: IntoIterator<Item = (&'a Task, u32, u32)>,
+    {
+        let mut makespan = 0;
+        let mut waits = vec![];
+        let mut total_turnaround = 0_u64;
+        let mut missed_start_by = 0;
+
+        for (task, started_at, finished_at) in runs {
+            let wait = started_at.saturating_sub(task.ready_at());
+            makespan = makespan.max(finished_at);
+            waits.push(wait);
+            total_turnaround += u64::from(finished_at.saturating_sub(task.queued_at));
+            if task.start_by.is_some_and(|t| started_at > t) {
+                missed_start_by += 1;
+            }
+        }
+
+        waits.sort_unstable();
+        let count = waits.len();
+        let mean = |total: u64| {
+            if count == 0 {
+                0.0
+            } else {
+                total as f64 / count as f64
+            }
+        };
+
+        ScheduleStats {
+            makespan,
+            mean_wait: mean(waits.iter().map(|&w| u64::from(w)).sum()),
+            max_wait: waits.last().copied().unwrap_or(0),
+            p99_wait: if count == 0 {
+                0
+            } else {
+                nearest_rank(&waits, 99)
+            },
+            mean_turnaround: mean(total_turnaround),
+            missed_start_by,
+        }
+    }
+}
+
+impl From<&Schedule> for ScheduleStats {
+    fn from(schedule: &Schedule) -> Self {
+        ScheduleStats::from_runs(
+            schedule
+                .runs
+                .iter()
+                .map(|run| (&run.task, run.started_at, run.finished_at)),
+        )
+    }
+}
+
+/// The `p`th percentile of `sorted` by the nearest-rank method. Panics if `sorted` is empty.
+pub(crate) fn nearest_rank(sorted: &[u32], p: usize) -> u32 {
+    sorted[(sorted.len() * p).div_ceil(100).max(1) - 1]
+}
+
+/// Runs `workload` in each of the given `orders` and scores the result, so orderings produced
+/// elsewhere (a heuristic, a learned model, ...) can be evaluated under the simulator's timing
+/// rules: one task at a time, no preemption, and no task started before it is ready. The CPU
+/// idles when the next task in the order isn't ready yet, even if a later one is.
+///
+/// Reservations are not enforced, as the order alone decides what runs next.
+///
+/// Panics if an order is not a permutation of the workload's task ids.
+pub fn score_orders(workload: &[Task], orders: &[Vec<u64>]) -> Vec<ScheduleStats> {
+    let tasks: BTreeMap<u64, &Task> = workload.iter().map(|task| (task.id, task)).collect();
+    assert_eq!(
+        tasks.len(),
+        workload.len(),
+        "workload has duplicate task ids"
+    );
+
+    orders
+        .iter()
+        .map(|order| {
+            assert_eq!(
+                order.len(),
+                workload.len(),
+                "order {:?} does not cover every task exactly once",
+                order
+            );
+
+            let mut time = 0;
+            let mut seen = BTreeSet::new();
+            let projections: Vec<Projection> = order
+                .iter()
+                .map(|id| {
+                    let task = tasks
+                        .get(id)
+                        .unwrap_or_else(|| panic!("order refers to unknown task {}", id));
+                    assert!(seen.insert(*id), "order runs task {} twice", id);
+
+                    let started_at = time.max(task.ready_at());
+                    time = started_at + task.execution_duration;
+                    Projection {
+                        id: *id,
+                        started_at,
+                        finished_at: time,
+                    }
+                })
+                .collect();
+
+            ScheduleStats::from_projections(workload, &projections)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sim::predict_finish_times;
+    use crate::{execution_order, RoundRobinScheduler, Scheduler};
+
+    fn workload() -> Vec<Task> {
+        vec![
+            Task {
+                id: 42,
+                queued_at: 0,
+                execution_duration: 3,
+                ..Default::default()
+            },
+            Task {
+                id: 43,
+                queued_at: 1,
+                execution_duration: 3,
+                ..Default::default()
+            },
+            Task {
+                id: 44,
+                queued_at: 2,
+                execution_duration: 2,
+                start_by: Some(4),
+                ..Default::default()
+            },
+        ]
+    }
+
+    #[test]
+    fn scores_each_order() {
+        let stats = score_orders(&workload(), &[vec![42, 44, 43], vec![42, 43, 44]]);
+
+        // 42: 0-3, 44: 3-5, 43: 5-8
+        assert_eq!(stats[0].makespan, 8);
+        assert_eq!(stats[0].max_wait, 4);
+        assert_eq!(stats[0].p99_wait, 4);
+        assert_eq!(stats[0].mean_wait, 5.0 / 3.0);
+        assert_eq!(stats[0].mean_turnaround, (3.0 + 3.0 + 7.0) / 3.0);
+        assert_eq!(stats[0].missed_start_by, 0);
+
+        // 42: 0-3, 43: 3-6, 44: 6-8
+        assert_eq!(stats[1].makespan, 8);
+        assert_eq!(stats[1].max_wait, 4);
+        assert_eq!(stats[1].mean_wait, 6.0 / 3.0);
+        assert_eq!(stats[1].missed_start_by, 1);
+    }
+
+    #[test]
+    fn scoring_the_sjf_order_matches_simulation() {
+        let order = execution_order(workload());
+
+        assert_eq!(
+            score_orders(&workload(), &[order]),
+            vec![ScheduleStats::from_projections(
+                &workload(),
+                &predict_finish_times(workload())
+            )]
+        );
+    }
+
+    #[test]
+    fn stats_of_a_schedule_match_its_projections() {
+        let schedule = RoundRobinScheduler { quantum: 1 }.schedule(workload());
+
+        assert_eq!(
+            ScheduleStats::from(&schedule),
+            ScheduleStats::from_projections(&workload(), &schedule.projections())
+        );
+    }
+
+    #[test]
+    fn scored_order_idles_for_unready_task() {
+        // running 43 first means waiting for it to be queued at 1
+        let stats = score_orders(&workload(), &[vec![43, 42, 44]]);
+
+        assert_eq!(stats[0].makespan, 9);
+    }
+
+    #[test]
+    #[should_panic(expected = "twice")]
+    fn order_with_repeated_task_panics() {
+        score_orders(&workload(), &[vec![42, 42, 43]]);
+    }
+}
@@ -0,0 +1,80 @@
+/// A unit of work for the simulated CPU, plus the optional constraints on when it may run.
+///
+/// In JSON, any field may be left out and takes its default.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct Task {
+    pub id: u64,
+    pub queued_at: u32,
+    pub execution_duration: u32,
+    /// Earliest time the task may start, even if it was queued before then.
+    pub not_before: Option<u32>,
+    /// Latest time the task should start by; see `missed_start_by`.
+    pub start_by: Option<u32>,
+    /// Reserves the CPU for this task from the given time. Other tasks are only started ahead of
+    /// a reservation if they will finish before it begins. If reservations overlap, the later one
+    /// starts as soon as the CPU is free.
+    pub reserved_at: Option<u32>,
+    /// How long the task will wait, once ready, for the CPU before it is abandoned. A task that
+    /// hasn't started by then never runs and is reported as `Expired`. Reserved tasks never
+    /// give up.
+    pub give_up_after: Option<u32>,
+    /// When the task should be finished by. `EdfScheduler` runs the earliest deadline first;
+    /// under any policy, `ScheduledTask::missed_deadline` flags a task that finished later.
+    pub deadline: Option<u32>,
+    /// What missing `deadline` means for this task; see `DeadlineReport`.
+    pub deadline_kind: DeadlineKind,
+    /// Ids of tasks that must finish before this one becomes eligible to run. Honoured by
+    /// `SjfScheduler` and `predict_with_dependencies`; see `dag::validate`.
+    pub depends_on: Vec<u64>,
+    /// Higher runs first under `PriorityScheduler`; tasks without one run after all that have one.
+    /// Other policies ignore it.
+    pub priority: Option<u8>,
+    /// Identifies the environment the task runs in (a container image, say). A warm slot left
+    /// behind by a task with the same tag lets this one skip its `cold_start`.
+    pub tag: Option<u64>,
+    /// Extra time needed to start the task when no warm slot matches its tag. Only modelled by
+    /// `predict_with_warm_pool`.
+    pub cold_start: u32,
+    /// Background work that only runs while the CPU would otherwise be idle, and gets off it as
+    /// soon as real work arrives. Only modelled by `FillerScheduler`.
+    pub filler: bool,
+    /// What each second between the task becoming ready and finishing costs; see
+    /// `ScheduledTask::delay_cost`. `CostOfDelayScheduler` orders by it.
+    pub delay_cost: u32,
+    /// How much of the CPU the task is entitled to relative to others, from -20 (the most) to
+    /// 19 (the least), as with the Unix `nice`. Only `FairScheduler` honours it.
+    pub nice: i8,
+}
+
+/// How much a missed deadline matters.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
+pub enum DeadlineKind {
+    /// Finishing late is a cost, growing with how late.
+    #[default]
+    Soft,
+    /// Finishing late at all makes the schedule unacceptable.
+    Hard,
+}
+
+impl Task {
+    /// Parses a JSON array of tasks.
+    #[cfg(feature = "serde")]
+    pub fn from_json_slice(json: &[u8]) -> serde_json::Result<Vec<Task>> {
+        serde_json::from_slice(json)
+    }
+
+    /// The moment the task becomes eligible to run.
+    pub(crate) fn ready_at(&self) -> u32 {
+        self.not_before
+            .map_or(self.queued_at, |t| t.max(self.queued_at))
+    }
+
+    /// The moment a reserved task is due to start, never before it is ready.
+    pub(crate) fn reserved_start(&self) -> Option<u32> {
+        self.reserved_at.map(|t| t.max(self.ready_at()))
+    }
+}
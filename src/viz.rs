@@ -0,0 +1,304 @@
+// Renderings of a `Schedule` for people to look at.
+use crate::Schedule;
+
+/// A text Gantt chart with one row per task, in the order they started: `#` while the task
+/// holds a CPU and `.` while it is queued but not running. The first row is a ruler giving the
+/// last digit of each time unit.
+///
+/// ```text
+///     01234567
+/// #42 ###
+/// #44  ..##
+/// #43  ....###
+/// ```
+pub fn gantt_ascii(schedule: &Schedule) -> String {
+    if schedule.runs.is_empty() {
+        return String::new();
+    }
+    let mut runs: Vec<_> = schedule.runs.iter().collect();
+    runs.sort_by_key(|run| run.started_at);
+    let end = runs.iter().map(|run| run.finished_at).max().unwrap();
+    let labels: Vec<String> = runs.iter().map(|run| format!("#{}", run.task.id)).collect();
+    let width = labels.iter().map(String::len).max().unwrap();
+
+    let ruler: String = (0..end)
+        .map(|t| char::from_digit(t % 10, 10).unwrap())
+        .collect();
+    let mut chart = format!("{:width$} {}\n", "", ruler, width = width);
+
+    for (run, label) in runs.iter().zip(&labels) {
+        let running = |t: u32| {
+            schedule
+                .segments
+                .iter()
+                .any(|s| s.id == run.task.id && s.start <= t && t < s.end)
+        };
+        let bar: String = (0..run.finished_at)
+            .map(|t| {
+                if running(t) {
+                    '#'
+                } else if t >= run.task.queued_at {
+                    '.'
+                } else {
+                    ' '
+                }
+            })
+            .collect();
+        chart.push_str(&format!("{:width$} {}\n", label, bar, width = width));
+    }
+    chart
+}
+
+/// How `gantt_svg` lays out a chart.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SvgOptions {
+    /// Horizontal pixels per time unit.
+    pub scale: u32,
+    pub row_height: u32,
+    /// One lane per CPU, showing what each core ran, rather than one row per task.
+    pub per_core_lanes: bool,
+}
+
+impl Default for SvgOptions {
+    fn default() -> Self {
+        SvgOptions {
+            scale: 10,
+            row_height: 20,
+            per_core_lanes: false,
+        }
+    }
+}
+
+const LABEL_WIDTH: u32 = 60;
+
+/// An SVG Gantt chart. By default there is one row per task, in the order they started, with a
+/// bar for each stretch on a CPU over a line while it waits, and ticks marking when it was
+/// queued, started and finished. With `per_core_lanes`, each row is a CPU instead and bars are
+/// labelled with the task they ran.
+pub fn gantt_svg(schedule: &Schedule, opts: SvgOptions) -> String {
+    let end = schedule
+        .runs
+        .iter()
+        .map(|run| run.finished_at)
+        .max()
+        .unwrap_or(0);
+    let x = |t: u32| LABEL_WIDTH + t * opts.scale;
+    let h = opts.row_height;
+    let mut rows = vec![];
+
+    if opts.per_core_lanes {
+        for (core, timeline) in schedule.timelines().iter().enumerate() {
+            let y = core as u32 * h;
+            let mut row = vec![label(y, h, &format!("CPU {}", core))];
+            for s in timeline {
+                row.push(bar(x(s.start), y, x(s.end) - x(s.start), h));
+                row.push(format!(
+                    r#"<text x="{}" y="{}" font-size="{}">#{}</text>"#,
+                    x(s.start) + 2,
+                    y + h * 3 / 4,
+                    h / 2,
+                    s.id
+                ));
+            }
+            rows.push(row.join(""));
+        }
+    } else {
+        let mut runs: Vec<_> = schedule.runs.iter().collect();
+        runs.sort_by_key(|run| run.started_at);
+        for (i, run) in runs.iter().enumerate() {
+            let y = i as u32 * h;
+            let mid = y + h / 2;
+            let mut row = vec![
+                label(y, h, &format!("#{}", run.task.id)),
+                format!(
+                    r#"<line class="waiting" x1="{}" y1="{}" x2="{}" y2="{}" stroke="gray"/>"#,
+                    x(run.task.queued_at),
+                    mid,
+                    x(run.finished_at),
+                    mid
+                ),
+            ];
+            for s in schedule.segments.iter().filter(|s| s.id == run.task.id) {
+                row.push(bar(x(s.start), y, x(s.end) - x(s.start), h));
+            }
+            for (class, t) in [
+                ("queued", run.task.queued_at),
+                ("started", run.started_at),
+                ("finished", run.finished_at),
+            ] {
+                row.push(format!(
+                    r#"<line class="{}" x1="{}" y1="{}" x2="{}" y2="{}" stroke="black"/>"#,
+                    class,
+                    x(t),
+                    y,
+                    x(t),
+                    y + h
+                ));
+            }
+            rows.push(row.join(""));
+        }
+    }
+
+    let height = rows.len() as u32 * h;
+    let width = x(end);
+    format!(
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{}" height="{}" viewBox="0 0 {} {}">"#,
+        width, height, width, height
+    ) + &rows.join("\n")
+        + "</svg>\n"
+}
+
+fn label(y: u32, h: u32, text: &str) -> String {
+    format!(
+        r#"<text x="2" y="{}" font-size="{}">{}</text>"#,
+        y + h * 3 / 4,
+        h / 2,
+        text
+    )
+}
+
+fn bar(x: u32, y: u32, width: u32, h: u32) -> String {
+    format!(
+        r#"<rect x="{}" y="{}" width="{}" height="{}" fill="steelblue"/>"#,
+        x,
+        y + 2,
+        width,
+        h - 4
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{execution_schedule, MultiCoreScheduler, RoundRobinScheduler, Scheduler, Task};
+
+    #[test]
+    fn one_row_per_task_in_start_order() {
+        let tasks = vec![
+            Task {
+                id: 42,
+                queued_at: 0,
+                execution_duration: 3,
+                ..Default::default()
+            },
+            Task {
+                id: 43,
+                queued_at: 1,
+                execution_duration: 3,
+                ..Default::default()
+            },
+            Task {
+                id: 44,
+                queued_at: 1,
+                execution_duration: 2,
+                ..Default::default()
+            },
+        ];
+
+        assert_eq!(
+            gantt_ascii(&execution_schedule(tasks)),
+            "    01234567\n\
+             #42 ###\n\
+             #44  ..##\n\
+             #43  ....###\n"
+        );
+    }
+
+    #[test]
+    fn preempted_tasks_wait_between_slices() {
+        let tasks = vec![
+            Task {
+                id: 1,
+                queued_at: 0,
+                execution_duration: 3,
+                ..Default::default()
+            },
+            Task {
+                id: 10,
+                queued_at: 0,
+                execution_duration: 2,
+                ..Default::default()
+            },
+        ];
+
+        assert_eq!(
+            gantt_ascii(&RoundRobinScheduler { quantum: 1 }.schedule(tasks)),
+            "    01234\n\
+             #1  #.#.#\n\
+             #10 .#.#\n"
+        );
+    }
+
+    #[test]
+    fn empty_schedule() {
+        assert_eq!(gantt_ascii(&Schedule::default()), "");
+    }
+
+    fn count(haystack: &str, needle: &str) -> usize {
+        haystack.matches(needle).count()
+    }
+
+    #[test]
+    fn svg_row_per_task_with_markers() {
+        let tasks = vec![
+            Task {
+                id: 1,
+                queued_at: 0,
+                execution_duration: 3,
+                ..Default::default()
+            },
+            Task {
+                id: 10,
+                queued_at: 0,
+                execution_duration: 2,
+                ..Default::default()
+            },
+        ];
+
+        let svg = gantt_svg(
+            &RoundRobinScheduler { quantum: 1 }.schedule(tasks),
+            SvgOptions::default(),
+        );
+
+        assert!(svg.starts_with(
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="110" height="40" viewBox="0 0 110 40">"#
+        ));
+        assert!(svg.ends_with("</svg>\n"));
+        // #1 runs in three slices and #10 in two
+        assert_eq!(count(&svg, "<rect"), 5);
+        assert_eq!(count(&svg, r#"class="started""#), 2);
+        assert!(svg.contains(r#"<rect x="60" y="2" width="10" height="16""#));
+        assert!(svg.contains(">#10</text>"));
+    }
+
+    #[test]
+    fn svg_lane_per_core() {
+        let tasks: Vec<Task> = (1..=3)
+            .map(|id| Task {
+                id,
+                queued_at: 0,
+                execution_duration: 2,
+                ..Default::default()
+            })
+            .collect();
+        let opts = SvgOptions {
+            per_core_lanes: true,
+            ..Default::default()
+        };
+
+        let svg = gantt_svg(&MultiCoreScheduler::new(2).schedule(tasks), opts);
+
+        assert!(svg.contains(">CPU 0</text>") && svg.contains(">CPU 1</text>"));
+        assert!(!svg.contains("CPU 2"));
+        assert_eq!(count(&svg, "<rect"), 3);
+        assert!(svg.contains(r#"height="40""#));
+    }
+
+    #[test]
+    fn svg_of_empty_schedule() {
+        assert_eq!(
+            gantt_svg(&execution_schedule(vec![]), SvgOptions::default()),
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"60\" height=\"0\" viewBox=\"0 0 60 0\"></svg>\n"
+        );
+    }
+}
@@ -0,0 +1,769 @@
+// Utilities for inspecting and reshaping a workload (a list of tasks) before it is simulated.
+use crate::rng::SplitMix64;
+use crate::stats::nearest_rank;
+use crate::Task;
+use std::collections::{BTreeMap, BTreeSet};
+
+/// Strips identifying details from a workload so it can be shared, e.g. in a bug report.
+///
+/// - ids are remapped to `0..n`, preserving their relative order so ties still break the same
+///   way, and `depends_on` follows them (a dependency on a task missing from the workload gets
+///   an id past the others, so it is still reported as unknown)
+/// - tags are remapped the same way, so tasks that shared a tag still do
+/// - every timestamp is shifted so the first task is queued at 0, hiding when the trace was taken
+/// - timestamps and durations (including `cold_start` and `give_up_after`) are then multiplied
+///   by `time_scale` and rounded
+///
+/// The result is deterministic for a given input. An integer `time_scale` keeps the schedule
+/// identical (just stretched); fractional scales can merge or reorder nearby events.
+pub fn anonymize(tasks: Vec<Task>, time_scale: f64) -> Vec<Task> {
+    let known: BTreeSet<u64> = tasks.iter().map(|task| task.id).collect();
+    let unknown: BTreeSet<u64> = tasks
+        .iter()
+        .flat_map(|task| task.depends_on.iter().copied())
+        .filter(|id| !known.contains(id))
+        .collect();
+    let ids: BTreeMap<u64, u64> = known.into_iter().chain(unknown).zip(0..).collect();
+    let tags: BTreeMap<u64, u64> = tasks
+        .iter()
+        .filter_map(|task| task.tag)
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .zip(0..)
+        .collect();
+    let origin = tasks.iter().map(|task| task.queued_at).min().unwrap_or(0);
+
+    let scale = |t: u32| (f64::from(t) * time_scale).round() as u32;
+    let rebase = |t: u32| scale(t.saturating_sub(origin));
+
+    tasks
+        .into_iter()
+        .map(|task| Task {
+            id: ids[&task.id],
+            depends_on: task.depends_on.iter().map(|id| ids[id]).collect(),
+            tag: task.tag.map(|tag| tags[&tag]),
+            execution_duration: scale(task.execution_duration),
+            cold_start: scale(task.cold_start),
+            give_up_after: task.give_up_after.map(scale),
+            ..map_times(task, rebase)
+        })
+        .collect()
+}
+
+/// Applies `f` to every timestamp on `task` (but not its duration).
+fn map_times<F: Fn(u32) -> u32>(task: Task, f: F) -> Task {
+    Task {
+        queued_at: f(task.queued_at),
+        not_before: task.not_before.map(&f),
+        start_by: task.start_by.map(&f),
+        reserved_at: task.reserved_at.map(&f),
+        deadline: task.deadline.map(&f),
+        ..task
+    }
+}
+
+/// Compresses (`factor > 1`) or stretches (`factor < 1`) the gaps between arrivals, so the same
+/// tasks arrive `factor` times as fast. Times are scaled relative to the first arrival, which
+/// stays put, and rounded to the nearest second.
+pub fn scale_arrival_rate(tasks: Vec<Task>, factor: f64) -> Vec<Task> {
+    assert!(factor > 0.0, "arrival rate factor must be positive");
+    let origin = tasks.iter().map(|task| task.queued_at).min().unwrap_or(0);
+    let scale = |t: u32| {
+        if t < origin {
+            return t;
+        }
+        origin + (f64::from(t - origin) / factor).round() as u32
+    };
+
+    tasks
+        .into_iter()
+        .map(|task| map_times(task, scale))
+        .collect()
+}
+
+/// Multiplies every execution duration, and cold start time, by `factor`, rounding to the
+/// nearest second.
+pub fn scale_durations(tasks: Vec<Task>, factor: f64) -> Vec<Task> {
+    assert!(factor >= 0.0, "duration factor must not be negative");
+    let scale = |t: u32| (f64::from(t) * factor).round() as u32;
+    tasks
+        .into_iter()
+        .map(|task| Task {
+            execution_duration: scale(task.execution_duration),
+            cold_start: scale(task.cold_start),
+            ..task
+        })
+        .collect()
+}
+
+/// Moves every timestamp by `offset` seconds. Times that would fall before 0 are clamped to 0.
+pub fn time_shift(tasks: Vec<Task>, offset: i64) -> Vec<Task> {
+    let shift = |t: u32| (i64::from(t) + offset).clamp(0, i64::from(u32::MAX)) as u32;
+    tasks
+        .into_iter()
+        .map(|task| map_times(task, shift))
+        .collect()
+}
+
+/// Keeps each task independently with probability `fraction`. The same `seed` always keeps the
+/// same tasks.
+pub fn sample(tasks: Vec<Task>, fraction: f64, seed: u64) -> Vec<Task> {
+    let mut rng = SplitMix64::stream(seed, "sample");
+    tasks
+        .into_iter()
+        .filter(|_| rng.next_f64() < fraction)
+        .collect()
+}
+
+/// Combines several workloads into one, ordered by `queued_at` (ties keep stream order). Ids are
+/// kept as they are, so streams are expected not to share ids.
+pub fn merge(streams: Vec<Vec<Task>>) -> Vec<Task> {
+    let mut merged: Vec<Task> = streams.into_iter().flatten().collect();
+    merged.sort_by_key(|task| task.queued_at);
+    merged
+}
+
+/// Summary of a workload, for telling whether a system is simply overloaded before blaming the
+/// scheduling policy. See `profile`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Profile {
+    pub task_count: usize,
+    pub first_arrival: u32,
+    pub last_arrival: u32,
+    /// Number of tasks queued in each consecutive `window`-second bucket, starting at
+    /// `first_arrival`.
+    pub arrivals_per_window: Vec<usize>,
+    pub durations: DurationSummary,
+    /// Sum of all execution durations, i.e. the CPU time the workload asks for.
+    pub total_work: u64,
+    /// Work arriving per second of wall time over the arrival span, relative to the single CPU's
+    /// capacity of 1.0. `None` if every task arrives at the same instant.
+    pub offered_load: Option<f64>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DurationSummary {
+    pub min: u32,
+    pub mean: f64,
+    pub p50: u32,
+    pub p90: u32,
+    pub p99: u32,
+    pub max: u32,
+}
+
+impl Profile {
+    /// Whether the workload asks for more CPU time than a single CPU can provide over its span.
+    pub fn is_overloaded(&self) -> bool {
+        self.offered_load.is_some_and(|load| load > 1.0)
+    }
+}
+
+/// Parameters fitted from a workload, from which statistically similar workloads can be
+/// generated without sharing the original trace. Arrivals are modelled as a Poisson process and
+/// durations by interpolating between the observed percentiles.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct WorkloadModel {
+    /// Mean tasks queued per second.
+    pub arrival_rate: f64,
+    pub durations: DurationSummary,
+}
+
+impl WorkloadModel {
+    /// Fits a model to a profile, e.g. one shared in a report instead of the trace itself.
+    /// Returns `None` if every task arrived at the same instant, as there is no rate to fit.
+    pub fn from_profile(profile: &Profile) -> Option<WorkloadModel> {
+        let span = profile.last_arrival - profile.first_arrival;
+        if span == 0 {
+            return None;
+        }
+        Some(WorkloadModel {
+            arrival_rate: (profile.task_count - 1) as f64 / f64::from(span),
+            durations: profile.durations,
+        })
+    }
+
+    /// Generates `count` tasks with ids `0..count`, the first queued at 0. The same `seed`
+    /// always generates the same workload.
+    pub fn generate(&self, count: usize, seed: u64) -> Vec<Task> {
+        self.generate_with(count, GeneratorSeeds::new(seed))
+    }
+
+    /// Like `generate`, with arrivals and durations drawn from separately seeded streams, e.g. to
+    /// vary durations while keeping the exact same arrivals.
+    pub fn generate_with(&self, count: usize, seeds: GeneratorSeeds) -> Vec<Task> {
+        let mut arrivals = SplitMix64::stream(seeds.arrivals, "arrivals");
+        let mut durations = SplitMix64::stream(seeds.durations, "durations");
+        self.tasks_from_uniforms(count, |_| arrivals.next_f64(), |_| durations.next_f64())
+    }
+
+    /// `replications` workloads of `count` tasks each, for Monte Carlo runs. The same `seed`
+    /// always gives the same set; see `Sampling` for how they relate to each other.
+    pub fn replications(
+        &self,
+        count: usize,
+        replications: usize,
+        seed: u64,
+        sampling: Sampling,
+    ) -> Vec<Vec<Task>> {
+        let mut seeds = SplitMix64::stream(seed, "replications");
+        match sampling {
+            Sampling::Independent => (0..replications)
+                .map(|_| self.generate(count, seeds.next_u64()))
+                .collect(),
+            Sampling::Antithetic => {
+                let mut all = vec![];
+                while all.len() < replications {
+                    let seed = seeds.next_u64();
+                    all.push(self.generate(count, seed));
+                    if all.len() < replications {
+                        let mut arrivals = SplitMix64::stream(seed, "arrivals");
+                        let mut durations = SplitMix64::stream(seed, "durations");
+                        all.push(self.tasks_from_uniforms(
+                            count,
+                            |_| 1.0 - arrivals.next_f64(),
+                            |_| 1.0 - durations.next_f64(),
+                        ));
+                    }
+                }
+                all
+            }
+            Sampling::Stratified => {
+                // Latin hypercube: for each task's gap and duration, each of the `replications`
+                // equal strata of [0, 1) is drawn from by exactly one replication
+                let mut rng = SplitMix64::stream(seeds.next_u64(), "strata");
+                let mut strata = || {
+                    let mut column: Vec<usize> = (0..replications).collect();
+                    for i in (1..column.len()).rev() {
+                        column.swap(i, rng.below(i as u64 + 1) as usize);
+                    }
+                    column
+                        .into_iter()
+                        .map(|stratum| (stratum as f64 + rng.next_f64()) / replications as f64)
+                        .collect::<Vec<f64>>()
+                };
+                let gaps: Vec<Vec<f64>> = (0..count).map(|_| strata()).collect();
+                let durations: Vec<Vec<f64>> = (0..count).map(|_| strata()).collect();
+                (0..replications)
+                    .map(|r| self.tasks_from_uniforms(count, |i| gaps[i][r], |i| durations[i][r]))
+                    .collect()
+            }
+        }
+    }
+
+    /// Builds `count` tasks, taking the uniform draw for task `i`'s arrival gap from `gap(i)`
+    /// and for its duration from `duration(i)`.
+    fn tasks_from_uniforms<A, D>(&self, count: usize, mut gap: A, mut duration: D) -> Vec<Task>
+    where
+        A: FnMut(usize) -> f64,
+        D: FnMut(usize) -> f64,
+    {
+        let mut arrival = 0.0_f64;
+        (0..count)
+            .map(|i| {
+                if i > 0 {
+                    // exponential gaps between arrivals; 1 - u avoids ln(0) for u in [0, 1)
+                    let u = gap(i).min(1.0 - f64::EPSILON);
+                    arrival += -(1.0 - u).ln() / self.arrival_rate;
+                }
+                Task {
+                    id: i as u64,
+                    queued_at: arrival.round() as u32,
+                    execution_duration: self.duration_at(duration(i)),
+                    ..Default::default()
+                }
+            })
+            .collect()
+    }
+
+    /// The duration at quantile `q`, linearly interpolated between the known percentiles.
+    fn duration_at(&self, q: f64) -> u32 {
+        let d = &self.durations;
+        let knots = [
+            (0.0, d.min),
+            (0.5, d.p50),
+            (0.9, d.p90),
+            (0.99, d.p99),
+            (1.0, d.max),
+        ];
+        let i = knots.iter().rposition(|&(at, _)| at <= q).unwrap().min(3);
+        let ((q0, d0), (q1, d1)) = (knots[i], knots[i + 1]);
+        let (d0, d1) = (f64::from(d0), f64::from(d1));
+        (d0 + (d1 - d0) * (q - q0) / (q1 - q0)).round() as u32
+    }
+}
+
+/// Seeds for each random component of `WorkloadModel::generate_with`. Each component draws from
+/// its own named stream, so the same seed in both fields still gives independent values.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GeneratorSeeds {
+    pub arrivals: u64,
+    pub durations: u64,
+}
+
+impl GeneratorSeeds {
+    /// The same seed for every component.
+    pub fn new(seed: u64) -> Self {
+        GeneratorSeeds {
+            arrivals: seed,
+            durations: seed,
+        }
+    }
+}
+
+/// How `WorkloadModel::replications` draws its workloads. The variance-reducing options need
+/// fewer replications than `Independent` for the same confidence in a mean.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Sampling {
+    /// Every replication is drawn independently.
+    Independent,
+    /// Replications come in pairs, the second mirroring every draw of the first (`u` becomes
+    /// `1 - u`), so a long gap in one is a short gap in the other.
+    Antithetic,
+    /// Latin hypercube: across replications, each task's gap and duration are spread evenly
+    /// over their distributions rather than leaving it to chance.
+    Stratified,
+}
+
+/// Fits a `WorkloadModel` to an observed trace; see `WorkloadModel::from_profile`.
+pub fn fit(tasks: &[Task]) -> Option<WorkloadModel> {
+    WorkloadModel::from_profile(&profile(tasks, 1)?)
+}
+
+/// Summarizes a workload before it is simulated: arrival rate over time (bucketed into windows of
+/// `window` seconds), the distribution of execution durations, and offered load against capacity.
+/// Returns `None` for an empty workload.
+///
+/// Panics if `window` is zero.
+pub fn profile(tasks: &[Task], window: u32) -> Option<Profile> {
+    assert!(window > 0, "profile window must be at least one second");
+
+    let first_arrival = tasks.iter().map(|task| task.queued_at).min()?;
+    let last_arrival = tasks.iter().map(|task| task.queued_at).max()?;
+
+    let mut arrivals_per_window = vec![0; ((last_arrival - first_arrival) / window) as usize + 1];
+    for task in tasks {
+        arrivals_per_window[((task.queued_at - first_arrival) / window) as usize] += 1;
+    }
+
+    let mut durations: Vec<u32> = tasks.iter().map(|task| task.execution_duration).collect();
+    durations.sort_unstable();
+    let total_work: u64 = durations.iter().map(|&d| u64::from(d)).sum();
+    let percentile = |p: usize| nearest_rank(&durations, p);
+
+    let span = last_arrival - first_arrival;
+
+    Some(Profile {
+        task_count: tasks.len(),
+        first_arrival,
+        last_arrival,
+        arrivals_per_window,
+        durations: DurationSummary {
+            min: durations[0],
+            mean: total_work as f64 / durations.len() as f64,
+            p50: percentile(50),
+            p90: percentile(90),
+            p99: percentile(99),
+            max: durations[durations.len() - 1],
+        },
+        total_work,
+        offered_load: if span == 0 {
+            None
+        } else {
+            Some(total_work as f64 / f64::from(span))
+        },
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::execution_order;
+
+    #[test]
+    fn anonymize_remaps_ids_and_rebases_times() {
+        let tasks = vec![
+            Task {
+                id: 9001,
+                queued_at: 1000,
+                execution_duration: 3,
+                start_by: Some(1004),
+                ..Default::default()
+            },
+            Task {
+                id: 42,
+                queued_at: 1002,
+                execution_duration: 1,
+                deadline: Some(1005),
+                ..Default::default()
+            },
+        ];
+
+        let anonymized = anonymize(tasks, 2.0);
+
+        assert_eq!(anonymized[0].id, 1);
+        assert_eq!(anonymized[0].queued_at, 0);
+        assert_eq!(anonymized[0].execution_duration, 6);
+        assert_eq!(anonymized[0].start_by, Some(8));
+        assert_eq!(anonymized[1].id, 0);
+        assert_eq!(anonymized[1].queued_at, 4);
+        assert_eq!(anonymized[1].execution_duration, 2);
+        assert_eq!(anonymized[1].deadline, Some(10));
+    }
+
+    #[test]
+    fn anonymize_remaps_dependencies_and_tags() {
+        let tasks = vec![
+            Task {
+                id: 9001,
+                tag: Some(0xC0FFEE),
+                ..Default::default()
+            },
+            Task {
+                id: 42,
+                depends_on: vec![9001, 77],
+                tag: Some(0xC0FFEE),
+                ..Default::default()
+            },
+            Task {
+                id: 50,
+                tag: Some(0xBEEF),
+                ..Default::default()
+            },
+        ];
+
+        let anonymized = anonymize(tasks, 1.0);
+
+        // #77 isn't in the workload, so gets the next id after the three that are
+        assert_eq!(anonymized[1].depends_on, vec![2, 3]);
+        let tags: Vec<Option<u64>> = anonymized.iter().map(|task| task.tag).collect();
+        assert_eq!(tags, vec![Some(1), Some(1), Some(0)]);
+    }
+
+    #[test]
+    fn anonymize_preserves_schedule() {
+        let tasks = || {
+            vec![
+                Task {
+                    id: 700,
+                    queued_at: 50,
+                    execution_duration: 3,
+                    ..Default::default()
+                },
+                Task {
+                    id: 300,
+                    queued_at: 51,
+                    execution_duration: 3,
+                    ..Default::default()
+                },
+                Task {
+                    id: 500,
+                    queued_at: 51,
+                    execution_duration: 3,
+                    ..Default::default()
+                },
+            ]
+        };
+
+        assert_eq!(execution_order(tasks()), vec![700, 300, 500]);
+        assert_eq!(execution_order(anonymize(tasks(), 1.0)), vec![2, 0, 1]);
+    }
+
+    #[test]
+    fn profile_summarizes_workload() {
+        let tasks: Vec<Task> = (0..10)
+            .map(|i| Task {
+                id: i,
+                queued_at: 100 + i as u32 * 2,
+                execution_duration: i as u32 + 1,
+                ..Default::default()
+            })
+            .collect();
+
+        let profile = profile(&tasks, 5).unwrap();
+
+        assert_eq!(profile.task_count, 10);
+        assert_eq!(profile.first_arrival, 100);
+        assert_eq!(profile.last_arrival, 118);
+        assert_eq!(profile.arrivals_per_window, vec![3, 2, 3, 2]);
+        assert_eq!(profile.durations.min, 1);
+        assert_eq!(profile.durations.p50, 5);
+        assert_eq!(profile.durations.p90, 9);
+        assert_eq!(profile.durations.max, 10);
+        assert_eq!(profile.durations.mean, 5.5);
+        assert_eq!(profile.total_work, 55);
+        // 55 seconds of work arriving over 18 seconds
+        assert!(profile.is_overloaded());
+    }
+
+    #[test]
+    fn profile_of_light_and_empty_workloads() {
+        let tasks = vec![
+            Task {
+                id: 1,
+                queued_at: 0,
+                execution_duration: 2,
+                ..Default::default()
+            },
+            Task {
+                id: 2,
+                queued_at: 10,
+                execution_duration: 3,
+                ..Default::default()
+            },
+        ];
+
+        let profile = profile(&tasks, 10).unwrap();
+        assert_eq!(profile.offered_load, Some(0.5));
+        assert!(!profile.is_overloaded());
+
+        assert_eq!(super::profile(&[], 10), None);
+    }
+
+    fn stream() -> Vec<Task> {
+        vec![
+            Task {
+                id: 1,
+                queued_at: 10,
+                execution_duration: 3,
+                cold_start: 2,
+                ..Default::default()
+            },
+            Task {
+                id: 2,
+                queued_at: 14,
+                execution_duration: 5,
+                start_by: Some(20),
+                ..Default::default()
+            },
+            Task {
+                id: 3,
+                queued_at: 18,
+                execution_duration: 1,
+                deadline: Some(22),
+                ..Default::default()
+            },
+        ]
+    }
+
+    fn queued_at(tasks: &[Task]) -> Vec<u32> {
+        tasks.iter().map(|task| task.queued_at).collect()
+    }
+
+    #[test]
+    fn doubling_arrival_rate_halves_gaps() {
+        let scaled = scale_arrival_rate(stream(), 2.0);
+
+        assert_eq!(queued_at(&scaled), vec![10, 12, 14]);
+        assert_eq!(scaled[1].start_by, Some(15));
+        assert_eq!(scaled[1].execution_duration, 5);
+        assert_eq!(scaled[2].deadline, Some(16));
+    }
+
+    #[test]
+    fn scaling_durations() {
+        let scaled = scale_durations(stream(), 1.5);
+
+        let durations: Vec<u32> = scaled.iter().map(|t| t.execution_duration).collect();
+        assert_eq!(durations, vec![5, 8, 2]);
+        assert_eq!(scaled[0].cold_start, 3);
+        assert_eq!(queued_at(&scaled), queued_at(&stream()));
+    }
+
+    #[test]
+    fn shifting_time() {
+        assert_eq!(queued_at(&time_shift(stream(), 5)), vec![15, 19, 23]);
+        assert_eq!(queued_at(&time_shift(stream(), -12)), vec![0, 2, 6]);
+        assert_eq!(time_shift(stream(), -12)[1].start_by, Some(8));
+        assert_eq!(time_shift(stream(), -12)[2].deadline, Some(10));
+    }
+
+    #[test]
+    fn sampling_is_deterministic() {
+        let tasks: Vec<Task> = (0..1000)
+            .map(|id| Task {
+                id,
+                ..Default::default()
+            })
+            .collect();
+
+        let sampled = sample(tasks.clone(), 0.25, 3);
+        let ids: Vec<u64> = sampled.iter().map(|t| t.id).collect();
+        let again: Vec<u64> = sample(tasks.clone(), 0.25, 3)
+            .iter()
+            .map(|t| t.id)
+            .collect();
+
+        assert_eq!(ids, again);
+        assert!(sampled.len() > 200 && sampled.len() < 300);
+        assert!(sample(tasks.clone(), 0.0, 3).is_empty());
+        assert_eq!(sample(tasks, 1.0, 3).len(), 1000);
+    }
+
+    #[test]
+    fn merging_streams_interleaves_by_arrival() {
+        let other = time_shift(anonymize(stream(), 1.0), 11)
+            .into_iter()
+            .map(|task| Task {
+                id: task.id + 100,
+                ..task
+            })
+            .collect();
+
+        let merged = merge(vec![stream(), other]);
+
+        let ids: Vec<u64> = merged.iter().map(|t| t.id).collect();
+        assert_eq!(ids, vec![1, 100, 2, 101, 3, 102]);
+    }
+
+    #[test]
+    fn fitted_model_reproduces_rate_and_durations() {
+        // one arrival every 2 seconds, durations 1..=100
+        let trace: Vec<Task> = (0..100)
+            .map(|i| Task {
+                id: i,
+                queued_at: 2 * i as u32,
+                execution_duration: i as u32 + 1,
+                ..Default::default()
+            })
+            .collect();
+        let model = fit(&trace).unwrap();
+        assert_eq!(model.arrival_rate, 0.5);
+
+        let synthetic = model.generate(10_000, 7);
+        let synthetic_profile = profile(&synthetic, 1).unwrap();
+
+        let rate = (synthetic.len() - 1) as f64 / f64::from(synthetic_profile.last_arrival);
+        assert!((rate - 0.5).abs() < 0.02, "rate {}", rate);
+        let durations = synthetic_profile.durations;
+        assert!(durations.min >= 1 && durations.max <= 100);
+        assert!((durations.p50 as i64 - 50).abs() <= 2, "{:?}", durations);
+        assert!((durations.p90 as i64 - 90).abs() <= 2, "{:?}", durations);
+        let times = |tasks: &[Task]| -> Vec<(u32, u32)> {
+            tasks
+                .iter()
+                .map(|t| (t.queued_at, t.execution_duration))
+                .collect()
+        };
+        assert_eq!(times(&model.generate(50, 7)), times(&synthetic[..50]));
+    }
+
+    #[test]
+    fn reseeding_durations_keeps_arrivals() {
+        let model = WorkloadModel {
+            arrival_rate: 0.5,
+            durations: DurationSummary {
+                min: 1,
+                mean: 10.0,
+                p50: 8,
+                p90: 20,
+                p99: 40,
+                max: 50,
+            },
+        };
+        let a = model.generate_with(100, GeneratorSeeds::new(1));
+        let b = model.generate_with(
+            100,
+            GeneratorSeeds {
+                durations: 2,
+                ..GeneratorSeeds::new(1)
+            },
+        );
+
+        let arrivals = |tasks: &[Task]| -> Vec<u32> { tasks.iter().map(|t| t.queued_at).collect() };
+        let lengths =
+            |tasks: &[Task]| -> Vec<u32> { tasks.iter().map(|t| t.execution_duration).collect() };
+        assert_eq!(arrivals(&a), arrivals(&b));
+        assert_ne!(lengths(&a), lengths(&b));
+    }
+
+    fn uniform_model() -> WorkloadModel {
+        // durations spread evenly over 0..=100, so duration tracks the quantile drawn
+        WorkloadModel {
+            arrival_rate: 1.0,
+            durations: DurationSummary {
+                min: 0,
+                mean: 50.0,
+                p50: 50,
+                p90: 90,
+                p99: 99,
+                max: 100,
+            },
+        }
+    }
+
+    #[test]
+    fn antithetic_pairs_mirror_each_other() {
+        let runs = uniform_model().replications(50, 5, 3, Sampling::Antithetic);
+
+        assert_eq!(runs.len(), 5);
+        for pair in runs.chunks(2).filter(|pair| pair.len() == 2) {
+            for (a, b) in pair[0].iter().zip(&pair[1]) {
+                let sum = a.execution_duration + b.execution_duration;
+                assert!(
+                    (99..=101).contains(&sum),
+                    "{} + {}",
+                    a.execution_duration,
+                    b.execution_duration
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn stratified_replications_cover_every_stratum() {
+        let runs = uniform_model().replications(20, 10, 3, Sampling::Stratified);
+
+        for i in 0..20 {
+            let mut durations: Vec<u32> =
+                runs.iter().map(|run| run[i].execution_duration).collect();
+            durations.sort_unstable();
+            // the kth draw is from [k/10, (k+1)/10), give or take rounding
+            for (k, &d) in durations.iter().enumerate() {
+                assert!(
+                    (10 * k as u32..=10 * k as u32 + 10).contains(&d),
+                    "{:?}",
+                    durations
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn replications_are_reproducible() {
+        let times = |runs: Vec<Vec<Task>>| -> Vec<(u32, u32)> {
+            runs.iter()
+                .flatten()
+                .map(|t| (t.queued_at, t.execution_duration))
+                .collect()
+        };
+        for sampling in [
+            Sampling::Independent,
+            Sampling::Antithetic,
+            Sampling::Stratified,
+        ] {
+            assert_eq!(
+                times(uniform_model().replications(10, 4, 9, sampling)),
+                times(uniform_model().replications(10, 4, 9, sampling))
+            );
+        }
+    }
+
+    #[test]
+    fn nothing_to_fit_without_an_arrival_span() {
+        let burst = vec![
+            Task {
+                id: 1,
+                execution_duration: 3,
+                ..Default::default()
+            },
+            Task {
+                id: 2,
+                execution_duration: 4,
+                ..Default::default()
+            },
+        ];
+
+        assert_eq!(fit(&burst), None);
+        assert_eq!(fit(&[]), None);
+    }
+}